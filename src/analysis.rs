@@ -0,0 +1,258 @@
+use crate::editor::FloatField;
+use crate::render::{self, Resolution};
+use crate::CrosshairProfile;
+
+/// Edges closer than this to a pixel boundary count as whole pixels.
+const EPSILON: f32 = 0.01;
+/// Scaled values this close to x.5 may round either way after a small change in resolution.
+const ROUNDING_MARGIN: f32 = 0.1;
+/// How many encodable steps around the current value are searched for suggestions.
+const SEARCH_STEPS: i32 = 30;
+
+/// Screen area of one crosshair part in monitor pixels, relative to the screen center.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extent {
+    pub part: String,
+    pub x0: f32,
+    pub y0: f32,
+    pub x1: f32,
+    pub y1: f32,
+}
+
+impl Extent {
+    pub fn width(&self) -> f32 {
+        self.x1 - self.x0
+    }
+
+    pub fn height(&self) -> f32 {
+        self.y1 - self.y0
+    }
+
+    fn is_fractional(&self) -> bool {
+        [self.x0, self.y0, self.x1, self.y1].iter().any(|v| !is_whole(*v))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub field: FloatField,
+    pub current: f32,
+    pub suggested: f32,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Analysis {
+    pub extents: Vec<Extent>,
+    pub findings: Vec<String>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+fn is_whole(value: f32) -> bool {
+    (value - value.round()).abs() < EPSILON
+}
+
+/// Number of axes on which `pixels` game pixels cover whole monitor pixels.
+fn sharp_axes(pixels: f32, scale: [f32; 2]) -> usize {
+    scale.iter().filter(|s| is_whole(pixels * *s)).count()
+}
+
+fn whole_on_monitor(pixels: f32, scale: [f32; 2]) -> bool {
+    sharp_axes(pixels, scale) == 2
+}
+
+fn near_rounding_edge(value: f32) -> bool {
+    (value.fract().abs() - 0.5).abs() < ROUNDING_MARGIN
+}
+
+/// Whether a value that scales to `raw` game pixels (drawn as `raw` rounded, at least
+/// `min`) is worth fixing, and if so, which candidate values are better.
+struct Rounding {
+    scale: [f32; 2],
+    min: i32,
+}
+
+impl Rounding {
+    fn pixels(&self, raw: f32) -> f32 {
+        render::round_to_int(raw).max(self.min) as f32
+    }
+
+    fn needs_fix(&self, raw: f32) -> bool {
+        near_rounding_edge(raw) || !whole_on_monitor(self.pixels(raw), self.scale)
+    }
+
+    /// Stable under rounding and sharp on at least as many axes, strictly more if `current`
+    /// was already stable.
+    fn improves(&self, current: f32, candidate: f32) -> bool {
+        let (now, then) = (sharp_axes(self.pixels(current), self.scale), sharp_axes(self.pixels(candidate), self.scale));
+        !near_rounding_edge(candidate) && then >= now && (then > now || near_rounding_edge(current))
+    }
+}
+
+/// The encodable value closest to `current` that satisfies `ok`.
+fn nearest(field: FloatField, current: f32, ok: impl Fn(f32) -> bool) -> Option<f32> {
+    let range = field.codec_range();
+    (1..=SEARCH_STEPS)
+        .flat_map(|k| [current - k as f32 * field.step(), current + k as f32 * field.step()])
+        .map(|value| field.snap(value))
+        .find(|value| range.contains(value) && ok(*value))
+}
+
+/// Pixel extents of every arm and the dot when the game runs at `resolution` and each game
+/// pixel covers `scale` monitor pixels, with findings about asymmetric or fractional edges
+/// and the nearest values that avoid them.
+pub fn analyze(profile: &CrosshairProfile, resolution: Resolution, scale: [f32; 2]) -> Analysis {
+    let m = render::metrics(profile, resolution);
+    let (cx, cy) = ((resolution.width / 2) as i32, (resolution.height / 2) as i32);
+    // The screen center lies on a pixel boundary only for even resolutions
+    let (center_x, center_y) = (resolution.width as f32 / 2.0, resolution.height as f32 / 2.0);
+    let segments = render::crosshair_segments(profile, &m, cx, cy, 0);
+    let extents: Vec<Extent> = segments.iter()
+        .map(|&render::Segment { part, rect: [x0, y0, x1, y1], .. }| Extent {
+            part: part.to_string(),
+            x0: (x0 as f32 - center_x) * scale[0],
+            y0: (y0 as f32 - center_y) * scale[1],
+            x1: (x1 as f32 - center_x) * scale[0],
+            y1: (y1 as f32 - center_y) * scale[1],
+        })
+        .collect();
+    let mut analysis = Analysis { extents, ..Default::default() };
+
+    // The innermost part of each arm, "inner left" on split styles
+    let find = |arm: &str| analysis.extents.iter().find(|e| e.part.rsplit(' ').next() == Some(arm));
+    let mut offsets = Vec::new();
+    if let (Some(left), Some(right)) = (find("left"), find("right")) {
+        offsets.push(("Left and right", (left.x1 + right.x0) / 2.0));
+    }
+    if let (Some(top), Some(bottom)) = (find("top"), find("bottom")) {
+        offsets.push(("Top and bottom", (top.y1 + bottom.y0) / 2.0));
+    }
+    for (arms, offset) in offsets {
+        if offset.abs() >= EPSILON {
+            analysis.findings.push(format!("{} arms are {:.2} px off the screen center ({} px thickness on a {} screen)", arms, offset, m.bar_thickness, resolution.label()));
+        }
+    }
+    let blurred: Vec<&str> = analysis.extents.iter().filter(|e| e.is_fractional()).map(|e| e.part.as_str()).collect();
+    if !blurred.is_empty() {
+        analysis.findings.push(format!("Edges between monitor pixels, drawn blurred: {}", blurred.join(", ")));
+    }
+
+    let thickness_px = render::yres(profile.thickness, resolution);
+    let rounding = Rounding { scale, min: 1 };
+    if rounding.needs_fix(thickness_px) {
+        let ok = |v: f32| rounding.improves(thickness_px, render::yres(v, resolution));
+        if let Some(suggested) = nearest(FloatField::Thickness, profile.thickness, ok) {
+            analysis.suggestions.push(Suggestion {
+                field: FloatField::Thickness,
+                current: profile.thickness,
+                suggested,
+                reason: format!("{:.2} px rounds to {} px; {} renders sharper", thickness_px, m.bar_thickness, suggested),
+            });
+        }
+    }
+
+    let size_px = render::yres(profile.size, resolution);
+    let rounding = Rounding { scale, min: 0 };
+    if m.bar_size > 0 && rounding.needs_fix(size_px) {
+        let ok = |v: f32| rounding.improves(size_px, render::yres(v, resolution));
+        if let Some(suggested) = nearest(FloatField::Size, profile.size, ok) {
+            analysis.suggestions.push(Suggestion {
+                field: FloatField::Size,
+                current: profile.size,
+                suggested,
+                reason: format!("length of {:.2} px rounds to {} px; {} is stable", size_px, m.bar_size, suggested),
+            });
+        }
+    }
+
+    let gap_field = FloatField::gap(profile);
+    let gap = gap_field.get(profile);
+    let gap_px = |v: f32| render::gap_pixels(v, resolution);
+    let rounding = Rounding { scale, min: i32::MIN };
+    if rounding.needs_fix(gap_px(gap)) {
+        let ok = |v: f32| rounding.improves(gap_px(gap), gap_px(v));
+        if let Some(suggested) = nearest(gap_field, gap, ok) {
+            analysis.suggestions.push(Suggestion {
+                field: gap_field,
+                current: gap,
+                suggested,
+                reason: format!("gap of {:.2} px rounds to {} px; {} is stable", gap_px(gap), m.distance, suggested),
+            });
+        }
+    }
+
+    if let Some(outline) = m.outline {
+        if !whole_on_monitor(outline, scale) {
+            analysis.findings.push(format!("Outline of {} px does not cover whole pixels and blends into its neighbours", outline));
+            if let Some(suggested) = nearest(FloatField::OutlineThickness, outline, |v| v > 0.0 && sharp_axes(v, scale) > sharp_axes(outline, scale)) {
+                analysis.suggestions.push(Suggestion {
+                    field: FloatField::OutlineThickness,
+                    current: outline,
+                    suggested,
+                    reason: format!("{} gives a sharp outline", suggested),
+                });
+            }
+        }
+    }
+    analysis
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::share_code;
+
+    #[test]
+    fn native_resolution_has_whole_pixel_extents() {
+        let result = analyze(&share_code::sample_profile(), Resolution::new(1920, 1080), [1.0, 1.0]);
+        assert!(result.extents.iter().all(|e| !e.is_fractional()));
+        let (left, right) = (&result.extents[0], &result.extents[1]);
+        assert_eq!(left.width(), right.width());
+    }
+
+    #[test]
+    fn stretched_resolution_flags_blur_and_suggests_values() {
+        let game = Resolution::new(1280, 960);
+        let scale = render::AspectMode::Stretched.scale(game, Resolution::new(1920, 1080));
+        let mut profile = share_code::sample_profile();
+        profile.thickness = 0.6;
+        let result = analyze(&profile, game, scale);
+        assert!(result.findings.iter().any(|f| f.contains("blurred")), "{:?}", result.findings);
+        let suggestion = result.suggestions.iter().find(|s| s.field == FloatField::Thickness).expect("thickness suggestion");
+        let px = render::round_to_int(render::yres(suggestion.suggested, game)) as f32;
+        assert!(sharp_axes(px, scale) > 0);
+    }
+
+    #[test]
+    fn extents_are_named_after_the_parts_that_are_drawn() {
+        let parts = |profile: &CrosshairProfile| -> Vec<String> {
+            analyze(profile, Resolution::new(1920, 1080), [1.0, 1.0]).extents.into_iter().map(|e| e.part).collect()
+        };
+        let mut dot_only = share_code::sample_profile();
+        (dot_only.size, dot_only.dot) = (0.0, true);
+        assert_eq!(parts(&dot_only), ["dot"]);
+
+        let mut split = share_code::sample_profile();
+        (split.style, split.size, split.t, split.dot) = (2, 4.0, true, true);
+        split.dynamic_maxdist_split_ratio = 0.0;
+        assert_eq!(parts(&split), ["inner left", "inner right", "inner bottom", "dot"]);
+        split.dynamic_maxdist_split_ratio = 1.0;
+        assert_eq!(parts(&split), ["outer left", "outer right", "outer bottom", "dot"]);
+    }
+
+    #[test]
+    fn default_styles_check_the_gap_they_are_drawn_with() {
+        let mut profile = share_code::sample_profile();
+        (profile.gap, profile.fixed_gap, profile.gap_use_weapon_value) = (0.0, 0.5, false);
+        let gap_suggestion = |profile: &CrosshairProfile| {
+            analyze(profile, Resolution::new(1280, 960), [1.0, 1.0]).suggestions.into_iter().find(|s| matches!(s.field, FloatField::Gap | FloatField::FixedGap))
+        };
+        // (4 + 0.5) * 960 / 1080 = 4.0 is stable, (4 + 0) * 960 / 1080 = 3.56 is not
+        profile.style = 4;
+        assert_eq!(gap_suggestion(&profile).map(|s| s.field), Some(FloatField::Gap));
+        profile.style = 1;
+        assert_eq!(gap_suggestion(&profile), None);
+        profile.fixed_gap = 0.0;
+        assert_eq!(gap_suggestion(&profile).map(|s| s.field), Some(FloatField::FixedGap));
+    }
+}
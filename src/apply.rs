@@ -0,0 +1,136 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::compare::{self, FieldValue};
+use crate::kv3::{self, Object, Value};
+use crate::vcfg::{self, VcfgFile, VcfgKind};
+use crate::CrosshairProfile;
+
+/// Crosshair convars the game stores as integers, every other number is a float.
+const INTEGER_CONVARS: [&str; 6] = [
+    "cl_crosshaircolor",
+    "cl_crosshaircolor_r",
+    "cl_crosshaircolor_g",
+    "cl_crosshaircolor_b",
+    "cl_crosshairstyle",
+    "cl_crosshair_dynamic_splitdist",
+];
+
+const AUTOEXEC_FILE: &str = "autoexec.cfg";
+const AUTOEXEC_BEGIN: &str = "// cs2man crosshair begin";
+const AUTOEXEC_END: &str = "// cs2man crosshair end";
+
+/// A convar value formatted the way the game writes it to its vcfg files.
+pub fn format_value(name: &str, value: FieldValue) -> String {
+    match value {
+        FieldValue::Bool(b) => b.to_string(),
+        FieldValue::Number(n) if INTEGER_CONVARS.contains(&name) => format!("{}", n.round() as i64),
+        FieldValue::Number(n) => format!("{:.6}", n),
+    }
+}
+
+/// `(convar, value)` pairs for every crosshair setting.
+pub fn crosshair_convars(profile: &CrosshairProfile) -> Vec<(&'static str, String)> {
+    compare::fields(profile).into_iter().map(|(name, value)| (name, format_value(name, value))).collect()
+}
+
+/// The user convars file the game reads for the main player: slot 0, or the lowest slot found.
+pub fn user_convars_file(files: &[VcfgFile]) -> Option<&VcfgFile> {
+    files.iter().filter(|f| f.kind == VcfgKind::UserConvars).min_by_key(|f| f.slot.unwrap_or(u32::MAX))
+}
+
+/// The value of a convar in the `convars` section of a user convars document.
+pub fn convar<'a>(document: &'a kv3::Document, name: &str) -> Option<&'a str> {
+    document.root_object()?.get("convars")?.as_object()?.get(name)?.as_str()
+}
+
+/// Sets `convars` inside the `convars` section of the document, keeping every other entry.
+pub fn set_convars<S: AsRef<str>>(document: &mut kv3::Document, convars: &[(S, String)]) -> Result<(), String> {
+    let root = document.root_object_mut().ok_or("The convars file has no root object")?;
+    let section = root.object_mut("convars");
+    for (name, value) in convars {
+        section.set(name.as_ref(), Value::String(value.clone()));
+    }
+    Ok(())
+}
+
+/// Writes `convars` into a user convars vcfg, creating a missing file. The existing file is
+/// backed up first when `backup` is set. Returns the backup path.
+pub fn write_convars<S: AsRef<str>>(path: &Path, convars: &[(S, String)], backup: bool) -> Result<Option<PathBuf>, String> {
+    let mut document = if path.exists() {
+        vcfg::read_document(path)?
+    } else {
+        kv3::Document::new(Object::new())
+    };
+    set_convars(&mut document, convars)?;
+    vcfg::write_document(path, &document, backup)
+}
+
+/// Writes the crosshair into a user convars vcfg, backing it up first when `backup` is set.
+/// A missing file is created. Returns the backup path.
+pub fn apply_to_vcfg(path: &Path, profile: &CrosshairProfile, backup: bool) -> Result<Option<PathBuf>, String> {
+    write_convars(path, &crosshair_convars(profile), backup)
+}
+
+/// Console commands that set the crosshair, one per line.
+pub fn crosshair_commands(profile: &CrosshairProfile) -> String {
+    crosshair_convars(profile).iter().map(|(name, value)| format!("{} \"{}\"", name, value)).collect::<Vec<_>>().join("\n")
+}
+
+/// Writes the crosshair commands into `autoexec.cfg` in `cfg_dir`, replacing an earlier
+/// block written by cs2man and keeping everything else. With `backup` the existing file is
+/// backed up first.
+pub fn write_autoexec(cfg_dir: &Path, profile: &CrosshairProfile, backup: bool) -> Result<PathBuf, String> {
+    let path = cfg_dir.join(AUTOEXEC_FILE);
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    let block = format!("{}\n{}\n{}", AUTOEXEC_BEGIN, crosshair_commands(profile), AUTOEXEC_END);
+    let content = match (existing.find(AUTOEXEC_BEGIN), existing.find(AUTOEXEC_END)) {
+        (Some(start), Some(end)) if end > start => format!("{}{}{}", &existing[..start], block, &existing[end + AUTOEXEC_END.len()..]),
+        _ if existing.trim().is_empty() => format!("{}\n", block),
+        _ => format!("{}\n\n{}\n", existing.trim_end(), block),
+    };
+    if backup && path.exists() {
+        vcfg::backup_file(&path)?;
+    }
+    fs::write(&path, content).map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::share_code;
+
+    #[test]
+    fn set_crosshair_keeps_other_convars() {
+        let text = "<!-- kv3 -->\n{\n\t\"convars\" =\n\t{\n\t\t\"sensitivity\" = \"1.200000\"\n\t\t\"cl_crosshairgap\" = \"1.000000\"\n\t}\n}\n";
+        let mut document = kv3::parse(text).unwrap();
+        let profile = share_code::sample_profile();
+        set_convars(&mut document, &crosshair_convars(&profile)).unwrap();
+        let convars = document.root_object().and_then(|root| root.get("convars")).and_then(Value::as_object).unwrap();
+        assert_eq!(convars.get("sensitivity").and_then(Value::as_str), Some("1.200000"));
+        assert_eq!(convars.get("cl_crosshairgap").and_then(Value::as_str), Some(format!("{:.6}", profile.gap).as_str()));
+        assert_eq!(convars.get("cl_crosshairstyle").and_then(Value::as_str), Some(profile.style.to_string().as_str()));
+        assert_eq!(convars.iter().next().map(|(k, _)| k), Some("sensitivity"));
+    }
+
+    #[test]
+    fn autoexec_block_is_replaced_and_the_rest_kept() {
+        let dir = std::env::temp_dir().join(format!("cs2man_autoexec_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(AUTOEXEC_FILE);
+        fs::write(&path, "bind f +lookatweapon\n").unwrap();
+        let mut profile = share_code::sample_profile();
+        write_autoexec(&dir, &profile, false).unwrap();
+        profile.gap = -3.0;
+        write_autoexec(&dir, &profile, false).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("bind f +lookatweapon\n\n"));
+        assert_eq!(content.matches(AUTOEXEC_BEGIN).count(), 1);
+        assert!(content.contains("cl_crosshairgap \"-3.000000\""), "{}", content);
+        assert!(content.trim_end().ends_with(AUTOEXEC_END));
+        let files = fs::read_dir(&dir).unwrap().count();
+        assert_eq!(files, 1, "no backups without the backup setting");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::kv3::{self, Object, Value};
+use crate::vcfg::{self, VcfgFile, VcfgKind};
+
+/// Value the game writes for keys that were explicitly unbound.
+pub const UNBOUND: &str = "<unbound>";
+const SECTION: &str = "bindings";
+
+/// Actions every player needs on some key; a missing one is reported as a conflict.
+pub const ESSENTIAL_ACTIONS: [&str; 16] = [
+    "+attack", "+attack2", "+forward", "+back", "+left", "+right", "+jump", "+duck",
+    "+sprint", "+reload", "+use", "slot1", "slot2", "slot3", "drop", "+showscores",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    pub key: String,
+    pub command: String,
+}
+
+impl Binding {
+    /// The individual commands of `"+jump; +duck"` style binds.
+    pub fn actions(&self) -> Vec<&str> {
+        self.command.split(';').map(str::trim).filter(|a| !a.is_empty()).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// The same key appears more than once (the game keys are case insensitive).
+    SharedKey { key: String, commands: Vec<String> },
+    /// One key runs several actions at once, like a jump-throw bind.
+    MultipleActions { key: String, actions: Vec<String> },
+    /// An essential action is bound to no key.
+    Unbound { action: String },
+}
+
+impl Conflict {
+    pub fn message(&self) -> String {
+        match self {
+            Conflict::SharedKey { key, commands } => format!("{} is bound more than once: {}", key, commands.join(" / ")),
+            Conflict::MultipleActions { key, actions } => format!("{} runs {} actions at once: {}", key, actions.len(), actions.join("; ")),
+            Conflict::Unbound { action } => format!("{} has no key", action),
+        }
+    }
+}
+
+/// The key bindings file the game uses for the main player: slot 0, or the lowest slot found.
+pub fn keys_file(files: &[VcfgFile]) -> Option<&VcfgFile> {
+    files.iter().filter(|f| f.kind == VcfgKind::KeyBindings).min_by_key(|f| f.slot.unwrap_or(u32::MAX))
+}
+
+/// Every bound key in file order, explicitly unbound keys are skipped.
+pub fn read_bindings(document: &kv3::Document) -> Vec<Binding> {
+    let Some(section) = document.root_object().and_then(|root| root.get(SECTION)).and_then(Value::as_object) else { return Vec::new() };
+    section.iter()
+        .filter_map(|(key, value)| Some(Binding { key: key.to_string(), command: value.as_str()?.to_string() }))
+        .filter(|b| b.command != UNBOUND)
+        .collect()
+}
+
+/// Replaces the bindings of `document`. Keys that were bound before but are missing from
+/// `bindings` are written as unbound, so the game does not fall back to its defaults.
+pub fn write_bindings(document: &mut kv3::Document, bindings: &[Binding]) -> Result<(), String> {
+    let root = document.root_object_mut().ok_or("The bindings file has no root object")?;
+    let section = root.object_mut(SECTION);
+    let removed: Vec<String> = section.iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !bindings.iter().any(|b| b.key.eq_ignore_ascii_case(key)))
+        .collect();
+    for key in removed {
+        section.set(&key, Value::String(UNBOUND.to_string()));
+    }
+    for binding in bindings {
+        // Reuse the spelling already in the file so a rebind does not add a second entry
+        let existing = section.iter().map(|(key, _)| key.to_string()).find(|key| key.eq_ignore_ascii_case(&binding.key));
+        section.set(existing.as_deref().unwrap_or(&binding.key), Value::String(binding.command.clone()));
+    }
+    Ok(())
+}
+
+pub fn conflicts(bindings: &[Binding]) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    let mut by_key: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for binding in bindings {
+        by_key.entry(binding.key.to_ascii_uppercase()).or_default().push(binding.command.clone());
+    }
+    for (key, commands) in by_key.into_iter().filter(|(_, commands)| commands.len() > 1) {
+        conflicts.push(Conflict::SharedKey { key, commands });
+    }
+    for binding in bindings {
+        let actions = binding.actions();
+        if actions.len() > 1 {
+            conflicts.push(Conflict::MultipleActions { key: binding.key.clone(), actions: actions.iter().map(|a| a.to_string()).collect() });
+        }
+    }
+    for action in ESSENTIAL_ACTIONS {
+        if !bindings.iter().any(|b| b.actions().iter().any(|a| a.eq_ignore_ascii_case(action))) {
+            conflicts.push(Conflict::Unbound { action: action.to_string() });
+        }
+    }
+    conflicts
+}
+
+/// Loads the bindings file at `path`, or an empty document if it does not exist yet.
+pub fn load(path: &Path) -> Result<kv3::Document, String> {
+    if path.exists() { vcfg::read_document(path) } else { Ok(kv3::Document::new(Object::new())) }
+}
+
+/// A bindings file opened for editing.
+#[derive(Debug, Clone)]
+pub struct BindSet {
+    pub path: PathBuf,
+    pub document: kv3::Document,
+    pub bindings: Vec<Binding>,
+    /// Conflicts of `bindings`, updated by `refresh_conflicts` after every edit.
+    pub conflicts: Vec<Conflict>,
+}
+
+impl BindSet {
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        let document = load(&path)?;
+        let bindings = read_bindings(&document);
+        let conflicts = conflicts(&bindings);
+        Ok(Self { path, document, bindings, conflicts })
+    }
+
+    pub fn refresh_conflicts(&mut self) {
+        self.conflicts = conflicts(&self.bindings);
+    }
+
+    /// The command bound to `key`, compared case insensitively.
+    pub fn command(&self, key: &str) -> Option<&str> {
+        self.bindings.iter().find(|b| b.key.eq_ignore_ascii_case(key)).map(|b| b.command.as_str())
+    }
+
+    /// Binds `key` to `command`, replacing its previous command.
+    pub fn bind(&mut self, key: &str, command: &str) {
+        match self.bindings.iter_mut().find(|b| b.key.eq_ignore_ascii_case(key)) {
+            Some(binding) => binding.command = command.to_string(),
+            None => self.bindings.push(Binding { key: key.to_string(), command: command.to_string() }),
+        }
+        self.refresh_conflicts();
+    }
+
+    pub fn unbind(&mut self, key: &str) {
+        self.bindings.retain(|b| !b.key.eq_ignore_ascii_case(key));
+        self.refresh_conflicts();
+    }
+
+    pub fn save(&mut self, backup: bool) -> Result<Option<PathBuf>, String> {
+        write_bindings(&mut self.document, &self.bindings)?;
+        vcfg::write_document(&self.path, &self.document, backup)
+    }
+}
+
+/// Copies only the key bindings from one bindings file into another, leaving the rest of
+/// the target untouched. With `backup` the target is backed up first.
+pub fn copy_bindings(from: &Path, to: &Path, backup: bool) -> Result<Option<PathBuf>, String> {
+    let bindings = read_bindings(&vcfg::read_document(from)?);
+    let mut target = load(to)?;
+    write_bindings(&mut target, &bindings)?;
+    vcfg::write_document(to, &target, backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEYS: &str = "<!-- kv3 -->\n{\n\t\"bindings\" =\n\t{\n\t\t\"MOUSE1\" = \"+attack\"\n\t\t\"SPACE\" = \"+jump\"\n\t\t\"F\" = \"<unbound>\"\n\t\t\"ALT\" = \"+jump; -attack\"\n\t}\n}\n";
+
+    #[test]
+    fn reads_and_rewrites_bindings() {
+        let mut document = kv3::parse(KEYS).unwrap();
+        let mut bindings = read_bindings(&document);
+        assert_eq!(bindings.len(), 3);
+        bindings.retain(|b| b.key != "SPACE");
+        bindings.push(Binding { key: "mouse1".to_string(), command: "+attack2".to_string() });
+        bindings.retain(|b| b.command != "+attack");
+        write_bindings(&mut document, &bindings).unwrap();
+        let section = document.root_object().and_then(|r| r.get("bindings")).and_then(Value::as_object).unwrap();
+        assert_eq!(section.get("SPACE").and_then(Value::as_str), Some(UNBOUND));
+        assert_eq!(section.get("MOUSE1").and_then(Value::as_str), Some("+attack2"));
+        assert!(section.get("mouse1").is_none());
+    }
+
+    #[test]
+    fn detects_conflicts() {
+        let bindings = vec![
+            Binding { key: "MOUSE1".to_string(), command: "+attack".to_string() },
+            Binding { key: "mouse1".to_string(), command: "+use".to_string() },
+            Binding { key: "ALT".to_string(), command: "+jump; -attack".to_string() },
+        ];
+        let found = conflicts(&bindings);
+        assert!(found.iter().any(|c| matches!(c, Conflict::SharedKey { key, .. } if key == "MOUSE1")));
+        assert!(found.iter().any(|c| matches!(c, Conflict::MultipleActions { key, .. } if key == "ALT")));
+        assert!(found.iter().any(|c| matches!(c, Conflict::Unbound { action } if action == "+forward")));
+        assert!(!found.iter().any(|c| matches!(c, Conflict::Unbound { action } if action == "+attack")));
+    }
+
+    #[test]
+    fn copy_keeps_target_settings_and_respects_backup() {
+        let dir = std::env::temp_dir().join(format!("cs2man_binds_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (from, to) = (dir.join("from.vcfg"), dir.join("to.vcfg"));
+        std::fs::write(&from, KEYS).unwrap();
+        std::fs::write(&to, "<!-- kv3 -->\n{\n\t\"bindings\" =\n\t{\n\t\t\"E\" = \"+use\"\n\t}\n\t\"other\" = \"kept\"\n}\n").unwrap();
+        assert_eq!(copy_bindings(&from, &to, false).unwrap(), None);
+        let mut set = BindSet::open(to).unwrap();
+        assert_eq!(set.command("space"), Some("+jump"));
+        assert_eq!(set.command("E"), None);
+        assert_eq!(set.document.root_object().and_then(|r| r.get("other")).and_then(Value::as_str), Some("kept"));
+        assert!(set.conflicts.iter().any(|c| matches!(c, Conflict::MultipleActions { key, .. } if key == "ALT")));
+        set.unbind("ALT");
+        assert!(!set.conflicts.iter().any(|c| matches!(c, Conflict::MultipleActions { .. })));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
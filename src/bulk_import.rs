@@ -0,0 +1,127 @@
+use regex::Regex;
+
+use crate::share_code;
+use crate::similarity;
+use crate::CrosshairProfile;
+
+/// A share code found in free-form text, waiting for the user to confirm the import.
+#[derive(Debug, Clone)]
+pub struct FoundCode {
+    pub code: String,
+    pub name: String,
+    pub profile: CrosshairProfile,
+    pub selected: bool,
+    /// Name of a library entry with identical settings, if any.
+    pub duplicate_of: Option<String>,
+    /// Closest library entry within the near-duplicate distance, when not an exact duplicate.
+    pub similar_to: Option<String>,
+}
+
+const SEPARATORS: &[char] = &[':', '-', '–', '—', '=', '|', '>', '"', '\'', '`', '*', '(', ')', '[', ']', ',', ';'];
+const MAX_NAME_LEN: usize = 32;
+
+fn clean_name(text: &str) -> String {
+    let text = text.trim_matches(|c: char| c.is_whitespace() || SEPARATORS.contains(&c));
+    // Chat logs look like "[12:01] <bob> s1mple: CSGO-...", keep only the last part
+    let text = ["> ", "] ", ": ", " - ", " | "].iter()
+        .filter_map(|delim| text.rfind(delim).map(|pos| pos + delim.len()))
+        .max()
+        .map_or(text, |pos| &text[pos..]);
+    let text = text.trim_matches(|c: char| c.is_whitespace() || SEPARATORS.contains(&c));
+    if text.chars().count() > MAX_NAME_LEN {
+        return String::new();
+    }
+    text.to_string()
+}
+
+/// Guesses a name for the code at `start..end` of `line`: text in front of the code,
+/// then text after it, then a short preceding line that holds no code itself.
+fn infer_name(line: &str, start: usize, end: usize, previous_line: Option<&str>, code_re: &Regex) -> Option<String> {
+    let before = clean_name(&line[..start]);
+    if !before.is_empty() {
+        return Some(before);
+    }
+    let after = clean_name(code_re.split(&line[end..]).next().unwrap_or_default());
+    if !after.is_empty() {
+        return Some(after);
+    }
+    previous_line
+        .filter(|prev| !code_re.is_match(prev))
+        .map(clean_name)
+        .filter(|name| !name.is_empty())
+}
+
+/// Finds every share code with a valid checksum in `text`. Repeated codes are reported once.
+pub fn scan_text(text: &str, library: &[CrosshairProfile]) -> Vec<FoundCode> {
+    let code_re = Regex::new(r"\bCSGO(?:-[A-Za-z0-9]{5}){5}\b").expect("valid share code regex");
+    let mut found: Vec<FoundCode> = Vec::new();
+    let mut previous_line: Option<&str> = None;
+    for line in text.lines() {
+        for m in code_re.find_iter(line) {
+            let code = m.as_str();
+            if !share_code::is_valid(code) || found.iter().any(|f| f.code == code) {
+                continue;
+            }
+            let Ok(bytes) = share_code::decode_bytes(code) else { continue };
+            let name = infer_name(line, m.start(), m.end(), previous_line, &code_re)
+                .unwrap_or_else(|| format!("Imported_{}", &code[5..10]));
+            let profile = share_code::profile_from_bytes(&bytes, name.clone(), code);
+            let duplicate_of = library.iter()
+                .chain(found.iter().map(|f| &f.profile))
+                .find(|existing| share_code::same_settings(existing, &profile))
+                .map(|existing| existing.name.clone());
+            let similar_to = if duplicate_of.is_none() {
+                similarity::near_duplicate(library.iter().chain(found.iter().map(|f| &f.profile)), &profile)
+            } else {
+                None
+            };
+            found.push(FoundCode { code: code.to_string(), name, selected: duplicate_of.is_none(), profile, duplicate_of, similar_to });
+        }
+        if !line.trim().is_empty() {
+            previous_line = Some(line.trim());
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FALLEN: &str = "CSGO-TpORA-p9Ley-TLQ3P-HzXJY-U9z6A";
+    const ZYWOO: &str = "CSGO-H3Wb2-YV2FB-VPipW-dx2td-hej5P";
+
+    #[test]
+    fn clean_name_keeps_the_last_part_of_chat_lines() {
+        assert_eq!(clean_name("[12:01] <bob> s1mple: "), "s1mple");
+        assert_eq!(clean_name(" \"ZywOo\" - "), "ZywOo");
+        assert_eq!(clean_name("this sentence is far too long to be the name of a crosshair"), "");
+    }
+
+    #[test]
+    fn names_come_from_before_after_or_the_previous_line() {
+        let text = format!("s1mple: {}\n{} (ZywOo)\n\ndevice\n{}\n{}", FALLEN, ZYWOO, share_code::SAMPLE_CODE, share_code::SAMPLE_CODE);
+        let found = scan_text(&text, &[]);
+        let names: Vec<&str> = found.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["s1mple", "ZywOo", "device"]);
+        assert!(found.iter().all(|f| f.selected && f.duplicate_of.is_none()));
+        assert_eq!(scan_text(FALLEN, &[])[0].name, "Imported_TpORA");
+    }
+
+    #[test]
+    fn skips_codes_with_a_bad_checksum() {
+        let broken = "CSGO-jvnbx-S3xFK-iEJXD-Y27Nd-AO6FQ";
+        assert!(share_code::decode_bytes(broken).is_ok() && !share_code::is_valid(broken));
+        assert!(scan_text(&format!("broken {}", broken), &[]).is_empty());
+    }
+
+    #[test]
+    fn marks_codes_already_in_the_library_as_duplicates() {
+        let library = vec![CrosshairProfile { name: "Device (saved)".to_string(), ..share_code::sample_profile() }];
+        let found = scan_text(&format!("{}\n{}", share_code::SAMPLE_CODE, FALLEN), &library);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].duplicate_of.as_deref(), Some("Device (saved)"));
+        assert!(!found[0].selected);
+        assert!(found[1].duplicate_of.is_none() && found[1].selected);
+    }
+}
@@ -0,0 +1,116 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::convars::{self, ConvarCategory};
+use crate::export::{self, ExportBackground, ExportOptions};
+use crate::render::Resolution;
+use crate::CrosshairProfile;
+
+const USAGE: &str = "Usage:
+  cs2man export-library <output-dir> [--library <file>] [--resolution <WxH>] [--zoom <n>] [--size <px>] [--background <transparent|#rrggbb|image>]
+  cs2man convar [<name or text>] [--category <name>]";
+
+/// Handles command line subcommands. Returns the process exit code, or `None` to start the GUI.
+pub fn run(args: &[String]) -> Option<i32> {
+    let command: fn(&[String]) -> Result<(), String> = match args.first()?.as_str() {
+        "export-library" => export_library,
+        "convar" => convar,
+        "help" | "--help" | "-h" => help,
+        _ => return None,
+    };
+    attach_console();
+    match command(&args[1..]) {
+        Ok(()) => Some(0),
+        Err(e) => {
+            eprintln!("Error: {}\n\n{}", e, USAGE);
+            Some(1)
+        }
+    }
+}
+
+/// The binary uses the windows subsystem, so it has no console of its own. Attach to the
+/// console of the shell that started it so output is visible.
+#[cfg(windows)]
+fn attach_console() {
+    use winapi::um::wincon::{AttachConsole, ATTACH_PARENT_PROCESS};
+    // Fails when not started from a console, output is then discarded as before
+    unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+fn help(_args: &[String]) -> Result<(), String> {
+    println!("{}", USAGE);
+    Ok(())
+}
+
+fn option_value<'a>(args: &'a [String], i: &mut usize) -> Result<&'a str, String> {
+    *i += 1;
+    args.get(*i).map(String::as_str).ok_or_else(|| format!("Missing value for {}", args[*i - 1]))
+}
+
+fn export_library(args: &[String]) -> Result<(), String> {
+    let mut output: Option<PathBuf> = None;
+    let mut library_path = PathBuf::from("crosshair_profiles.json");
+    let mut options = ExportOptions::default();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--library" => library_path = PathBuf::from(option_value(args, &mut i)?),
+            "--resolution" => {
+                let value = option_value(args, &mut i)?;
+                options.resolution = Resolution::parse(value).ok_or_else(|| format!("Invalid resolution: {}", value))?;
+            }
+            "--zoom" => options.zoom = option_value(args, &mut i)?.parse().map_err(|_| "Invalid zoom".to_string())?,
+            "--size" => options.size = option_value(args, &mut i)?.parse().map_err(|_| "Invalid size".to_string())?,
+            "--background" => options.background = ExportBackground::parse(option_value(args, &mut i)?)?,
+            other if output.is_none() && !other.starts_with("--") => output = Some(PathBuf::from(other)),
+            other => return Err(format!("Unexpected argument: {}", other)),
+        }
+        i += 1;
+    }
+    let output = output.ok_or("Missing output directory")?;
+    let content = fs::read_to_string(&library_path).map_err(|e| format!("Could not read {}: {}", library_path.display(), e))?;
+    let library: Vec<CrosshairProfile> = serde_json::from_str(&content).map_err(|e| format!("Invalid library file: {}", e))?;
+    let written = export::export_library(&library, &output, &options)?;
+    for path in &written {
+        println!("{}", path.display());
+    }
+    println!("Rendered {} crosshairs into {}", written.len(), output.display());
+    Ok(())
+}
+
+/// Looks up the built-in convar database: full details for an exact name, otherwise a
+/// table of every entry matching the text.
+fn convar(args: &[String]) -> Result<(), String> {
+    let mut query = String::new();
+    let mut category = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--category" => {
+                let value = option_value(args, &mut i)?;
+                let names: Vec<&str> = ConvarCategory::ALL.iter().map(|c| c.label()).collect();
+                category = Some(ConvarCategory::parse(value).ok_or_else(|| format!("Unknown category {}, expected one of {}", value, names.join(", ")))?);
+            }
+            other if query.is_empty() && !other.starts_with("--") => query = other.to_string(),
+            other => return Err(format!("Unexpected argument: {}", other)),
+        }
+        i += 1;
+    }
+    if let Some(info) = convars::lookup(&query).filter(|info| category.is_none_or(|c| info.category == c)) {
+        println!("{}", info.describe());
+        return Ok(());
+    }
+    let matches = convars::search(&query, category);
+    println!("Convar database v{} ({}), {} match{}", convars::DATABASE_VERSION, convars::GAME_VERSION, matches.len(), if matches.len() == 1 { "" } else { "es" });
+    for info in matches {
+        let details = match info.removed {
+            Some(_) => "removed in CS2".to_string(),
+            None => format!("{} {} default {}", info.kind.label(), info.range_label().unwrap_or_default(), info.default),
+        };
+        println!("{:<42} {:<10} {}", info.name, info.category.label(), details);
+    }
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use crate::CrosshairProfile;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldValue {
+    Number(f32),
+    Bool(bool),
+}
+
+impl FieldValue {
+    pub fn as_f32(self) -> f32 {
+        match self {
+            FieldValue::Number(value) => value,
+            FieldValue::Bool(value) => value as u8 as f32,
+        }
+    }
+
+    pub fn display(self) -> String {
+        match self {
+            FieldValue::Number(value) => format!("{}", (value * 100.0).round() / 100.0),
+            FieldValue::Bool(value) => if value { "on" } else { "off" }.to_string(),
+        }
+    }
+}
+
+/// Every setting of a profile with its console variable name, in share code order.
+pub fn fields(p: &CrosshairProfile) -> Vec<(&'static str, FieldValue)> {
+    use FieldValue::{Bool, Number};
+    vec![
+        ("cl_crosshairgap", Number(p.gap)),
+        ("cl_crosshair_outlinethickness", Number(p.outline_thickness)),
+        ("cl_crosshaircolor_r", Number(p.red as f32)),
+        ("cl_crosshaircolor_g", Number(p.green as f32)),
+        ("cl_crosshaircolor_b", Number(p.blue as f32)),
+        ("cl_crosshairalpha", Number(p.alpha as f32)),
+        ("cl_crosshair_dynamic_splitdist", Number(p.dynamic_splitdist as f32)),
+        ("cl_crosshair_recoil", Bool(p.recoil)),
+        ("cl_fixedcrosshairgap", Number(p.fixed_gap)),
+        ("cl_crosshaircolor", Number(p.color as f32)),
+        ("cl_crosshair_drawoutline", Bool(p.draw_outline)),
+        ("cl_crosshair_dynamic_splitalpha_innermod", Number(p.dynamic_splitalpha_innermod)),
+        ("cl_crosshair_dynamic_splitalpha_outermod", Number(p.dynamic_splitalpha_outermod)),
+        ("cl_crosshair_dynamic_maxdist_splitratio", Number(p.dynamic_maxdist_split_ratio)),
+        ("cl_crosshairthickness", Number(p.thickness)),
+        ("cl_crosshairstyle", Number(p.style as f32)),
+        ("cl_crosshairdot", Bool(p.dot)),
+        ("cl_crosshairgap_useweaponvalue", Bool(p.gap_use_weapon_value)),
+        ("cl_crosshairusealpha", Bool(p.use_alpha)),
+        ("cl_crosshair_t", Bool(p.t)),
+        ("cl_crosshairsize", Number(p.size)),
+    ]
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    pub name: &'static str,
+    pub a: FieldValue,
+    pub b: FieldValue,
+}
+
+impl FieldDiff {
+    /// `b - a` for numeric settings, `None` for toggles.
+    pub fn delta(&self) -> Option<f32> {
+        match (self.a, self.b) {
+            (FieldValue::Number(a), FieldValue::Number(b)) => Some(b - a),
+            _ => None,
+        }
+    }
+}
+
+/// All settings that differ between `a` and `b`. Values are compared at share code
+/// precision, so float noise below the encodable step is ignored.
+pub fn diff_profiles(a: &CrosshairProfile, b: &CrosshairProfile) -> Vec<FieldDiff> {
+    fields(a).into_iter().zip(fields(b))
+        .filter(|((_, va), (_, vb))| (va.as_f32() - vb.as_f32()).abs() >= 0.05)
+        .map(|((name, a), (_, b))| FieldDiff { name, a, b })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::share_code;
+
+    #[test]
+    fn equal_profiles_have_no_differences() {
+        let profile = share_code::sample_profile();
+        assert!(diff_profiles(&profile, &profile.clone()).is_empty());
+    }
+
+    #[test]
+    fn reports_a_single_changed_field_with_its_delta() {
+        let a = share_code::sample_profile();
+        let mut b = a.clone();
+        b.gap = a.gap + 1.5;
+        b.name = "renamed".to_string();
+        let diff = diff_profiles(&a, &b);
+        assert_eq!(diff.len(), 1, "{:?}", diff);
+        assert_eq!(diff[0].name, "cl_crosshairgap");
+        assert!((diff[0].delta().unwrap() - 1.5).abs() < 1e-5);
+
+        b = a.clone();
+        b.dot = !a.dot;
+        let diff = diff_profiles(&a, &b);
+        assert_eq!((diff.len(), diff[0].name, diff[0].delta()), (1, "cl_crosshairdot", None));
+    }
+
+    #[test]
+    fn ignores_float_noise_below_the_encodable_step() {
+        let a = share_code::sample_profile();
+        let mut b = a.clone();
+        b.size = a.size + 0.04;
+        b.thickness = a.thickness - 0.001;
+        assert!(diff_profiles(&a, &b).is_empty());
+        b.size = a.size + 0.1;
+        assert_eq!(diff_profiles(&a, &b).iter().map(|d| d.name).collect::<Vec<_>>(), ["cl_crosshairsize"]);
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::apply;
+use crate::convars::{self, ConvarInfo};
+use crate::kv3;
+use crate::library;
+
+/// A typed convar value, written the way the game stores it in the user convars vcfg.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ConvarValue {
+    Bool(bool),
+    Int(i64),
+    Float(f32),
+}
+
+impl ConvarValue {
+    pub fn format(self) -> String {
+        match self {
+            ConvarValue::Bool(b) => b.to_string(),
+            ConvarValue::Int(i) => i.to_string(),
+            ConvarValue::Float(f) => format!("{:.6}", f),
+        }
+    }
+}
+
+/// How a convar is edited. Ranges and defaults come from the convar database.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConvarKind {
+    Bool,
+    Float { step: f32 },
+    /// An integer picked from named values, the index is the value.
+    Choice(&'static [&'static str]),
+}
+
+pub struct ConvarSpec {
+    pub name: &'static str,
+    pub label: &'static str,
+    pub kind: ConvarKind,
+}
+
+impl ConvarSpec {
+    /// The database entry, every spec has one (checked by the tests).
+    pub fn info(&self) -> &'static ConvarInfo {
+        convars::lookup(self.name).unwrap_or_else(|| panic!("{} is missing from the convar database", self.name))
+    }
+
+    pub fn range(&self) -> RangeInclusive<f32> {
+        self.info().range()
+    }
+
+    pub fn default(&self) -> ConvarValue {
+        self.parse(self.info().default).unwrap_or_else(|| panic!("{} has an invalid default", self.name))
+    }
+
+    /// Parses a value as written in a vcfg, clamped to the allowed range.
+    pub fn parse(&self, text: &str) -> Option<ConvarValue> {
+        let text = text.trim();
+        let range = self.range();
+        match self.kind {
+            ConvarKind::Bool => match text {
+                "true" | "1" => Some(ConvarValue::Bool(true)),
+                "false" | "0" => Some(ConvarValue::Bool(false)),
+                _ => None,
+            },
+            ConvarKind::Float { .. } => text.parse::<f32>().ok().map(|f| ConvarValue::Float(f.clamp(*range.start(), *range.end()))),
+            ConvarKind::Choice(names) => text.parse::<f32>().ok().map(|f| ConvarValue::Int((f.round() as i64).clamp(0, names.len() as i64 - 1))),
+        }
+    }
+}
+
+/// A group of convars that gets its own profile library. New categories only need a
+/// convar list here and an entry in [`CATEGORIES`].
+pub struct Category {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub convars: &'static [ConvarSpec],
+}
+
+impl Category {
+    pub fn library_file(&self) -> PathBuf {
+        PathBuf::from(format!("{}_profiles.json", self.id))
+    }
+}
+
+const fn float(name: &'static str, label: &'static str, step: f32) -> ConvarSpec {
+    ConvarSpec { name, label, kind: ConvarKind::Float { step } }
+}
+
+const fn flag(name: &'static str, label: &'static str) -> ConvarSpec {
+    ConvarSpec { name, label, kind: ConvarKind::Bool }
+}
+
+const HUD_COLORS: [&str; 11] = ["Default", "White", "Light blue", "Blue", "Purple", "Red", "Orange", "Yellow", "Green", "Aqua", "Pink"];
+
+const HUD: [ConvarSpec; 4] = [
+    float("hud_scaling", "HUD scale", 0.05),
+    ConvarSpec { name: "cl_hud_color", label: "HUD color", kind: ConvarKind::Choice(&HUD_COLORS) },
+    float("cl_hud_background_alpha", "Background alpha", 0.05),
+    flag("cl_showloadout", "Always show inventory"),
+];
+
+const RADAR: [ConvarSpec; 6] = [
+    float("cl_radar_scale", "Map zoom", 0.05),
+    float("cl_hud_radar_scale", "Radar size", 0.05),
+    float("cl_radar_icon_scale_min", "Icon scale", 0.05),
+    flag("cl_radar_always_centered", "Keep player centered"),
+    flag("cl_radar_rotate", "Rotate with player"),
+    flag("cl_radar_square_with_scoreboard", "Square with scoreboard"),
+];
+
+const AUDIO: [ConvarSpec; 7] = [
+    float("volume", "Master volume", 0.01),
+    float("snd_voipvolume", "Voice volume", 0.01),
+    float("snd_musicvolume", "Music volume", 0.01),
+    float("snd_menumusic_volume", "Main menu music", 0.01),
+    float("snd_roundstart_volume", "Round start music", 0.01),
+    float("snd_tensecondwarning_volume", "Ten second warning", 0.01),
+    float("snd_mvp_volume", "MVP anthem", 0.01),
+];
+
+pub const CATEGORIES: [Category; 3] = [
+    Category { id: "hud", title: "🖥 HUD", convars: &HUD },
+    Category { id: "radar", title: "🗺 Radar", convars: &RADAR },
+    Category { id: "audio", title: "🔊 Audio", convars: &AUDIO },
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConvarProfile {
+    pub name: String,
+    pub values: BTreeMap<String, ConvarValue>,
+    #[serde(default)]
+    pub added_at: u64,
+}
+
+impl LibraryProfile for ConvarProfile {
+    fn name_mut(&mut self) -> &mut String {
+        &mut self.name
+    }
+
+    fn set_added_at(&mut self, timestamp: u64) {
+        self.added_at = timestamp;
+    }
+}
+
+impl ConvarProfile {
+    /// A profile with the game's defaults for every convar of `category`.
+    pub fn new(category: &Category, name: String) -> Self {
+        let values = category.convars.iter().map(|spec| (spec.name.to_string(), spec.default())).collect();
+        Self { name, values, added_at: 0 }
+    }
+
+    /// Drops values of convars that are not in `category` or that do not fit their kind,
+    /// such as misspelled keys in a hand edited library file.
+    pub fn retain_known(&mut self, category: &Category) {
+        self.values.retain(|name, value| {
+            category.convars.iter().find(|spec| spec.name == name).is_some_and(|spec| matches!(
+                (spec.kind, value),
+                (ConvarKind::Bool, ConvarValue::Bool(_)) | (ConvarKind::Float { .. }, ConvarValue::Float(_)) | (ConvarKind::Choice(_), ConvarValue::Int(_))
+            ))
+        });
+    }
+
+    pub fn value(&self, spec: &ConvarSpec) -> ConvarValue {
+        self.values.get(spec.name).copied().unwrap_or_else(|| spec.default())
+    }
+
+    /// Reads the category's convars from a user convars document, missing or unreadable
+    /// values keep the defaults.
+    pub fn from_document(category: &Category, document: &kv3::Document, name: String) -> Self {
+        let mut profile = Self::new(category, name);
+        for spec in category.convars {
+            if let Some(value) = apply::convar(document, spec.name).and_then(|text| spec.parse(text)) {
+                profile.values.insert(spec.name.to_string(), value);
+            }
+        }
+        profile
+    }
+
+    /// `(convar, value)` pairs for every convar of `category`.
+    pub fn convars(&self, category: &Category) -> Vec<(&'static str, String)> {
+        category.convars.iter().map(|spec| (spec.name, self.value(spec).format())).collect()
+    }
+}
+
+/// Library and editor state of one category in the GUI.
+pub struct CategoryState {
+    pub library: Vec<ConvarProfile>,
+    pub active: ConvarProfile,
+    pub selected: Option<usize>,
+    pub targets: BTreeSet<usize>,
+}
+
+impl CategoryState {
+    pub fn load(category: &Category) -> Self {
+        let mut library: Vec<ConvarProfile> = fs::read_to_string(category.library_file()).ok().and_then(|content| serde_json::from_str(&content).ok()).unwrap_or_default();
+        for profile in &mut library {
+            profile.retain_known(category);
+        }
+        Self { library, active: ConvarProfile::new(category, "New profile".to_string()), selected: None, targets: BTreeSet::new() }
+    }
+
+    pub fn save(&self, category: &Category) {
+        if let Ok(content) = serde_json::to_string_pretty(&self.library) {
+            let _ = fs::write(category.library_file(), content);
+        }
+    }
+}
+
+/// One widget per convar of `category`. Returns true if anything changed.
+pub fn editor_ui(ui: &mut egui::Ui, category: &Category, profile: &mut ConvarProfile) -> bool {
+    let mut changed = false;
+    egui::Grid::new(format!("convar_editor_{}", category.id)).num_columns(2).show(ui, |ui| {
+        for spec in category.convars {
+            ui.label(spec.label).on_hover_text(spec.name);
+            let mut value = profile.value(spec);
+            let response = match (spec.kind, &mut value) {
+                (ConvarKind::Bool, ConvarValue::Bool(b)) => ui.checkbox(b, ""),
+                (ConvarKind::Float { step }, ConvarValue::Float(f)) => ui.add(egui::Slider::new(f, spec.range()).step_by(step as f64)),
+                (ConvarKind::Choice(names), ConvarValue::Int(i)) => {
+                    let mut response = egui::ComboBox::from_id_source(("convar_choice", spec.name))
+                        .selected_text(names.get(*i as usize).copied().unwrap_or("Unknown"))
+                        .show_ui(ui, |ui| {
+                            names.iter().enumerate().fold(false, |picked, (idx, name)| picked | ui.selectable_value(i, idx as i64, *name).changed())
+                        });
+                    if response.inner == Some(true) {
+                        response.response.mark_changed();
+                    }
+                    response.response
+                }
+                _ => ui.label("invalid value"),
+            };
+            if response.changed() {
+                profile.values.insert(spec.name.to_string(), value);
+                changed = true;
+            }
+            ui.end_row();
+        }
+    });
+    changed
+}
+
+/// A profile kept in one of the convar profile libraries (viewmodel, HUD, radar, …).
+pub trait LibraryProfile: Clone {
+    fn name_mut(&mut self) -> &mut String;
+    fn set_added_at(&mut self, timestamp: u64);
+}
+
+/// The shared list, name field and add/update buttons of the convar profile libraries.
+/// `extra` adds buttons after them and may replace the active profile. Returns true when
+/// `library` changed and should be saved.
+pub fn profile_library_ui<P: LibraryProfile>(
+    ui: &mut egui::Ui,
+    library: &mut Vec<P>,
+    selected: &mut Option<usize>,
+    active: &mut P,
+    label: impl Fn(&P) -> String,
+    extra: impl FnOnce(&mut egui::Ui, &mut P, &mut Option<usize>),
+) -> bool {
+    let mut changed = false;
+    let mut to_delete = None;
+    for (idx, profile) in library.iter().enumerate() {
+        ui.horizontal(|ui| {
+            if ui.selectable_label(*selected == Some(idx), label(profile)).clicked() {
+                *selected = Some(idx);
+                *active = profile.clone();
+            }
+            if ui.small_button("🗑").clicked() {
+                to_delete = Some(idx);
+            }
+        });
+    }
+    if let Some(idx) = to_delete {
+        library.remove(idx);
+        *selected = None;
+        changed = true;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("Name:");
+        ui.text_edit_singleline(active.name_mut());
+        if ui.button("➕ Add to Library").clicked() {
+            let mut profile = active.clone();
+            profile.set_added_at(library::now_timestamp());
+            library.push(profile);
+            *selected = Some(library.len() - 1);
+            changed = true;
+        }
+        if let Some(idx) = selected.filter(|&idx| idx < library.len()) {
+            if ui.button("💾 Update").clicked() {
+                library[idx] = active.clone();
+                changed = true;
+            }
+        }
+        extra(ui, active, selected);
+    });
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_round_trip_through_a_document() {
+        for category in &CATEGORIES {
+            let mut profile = ConvarProfile::new(category, "test".to_string());
+            for spec in category.convars {
+                let changed = match spec.kind {
+                    ConvarKind::Bool => ConvarValue::Bool(false),
+                    ConvarKind::Float { .. } => ConvarValue::Float(*spec.range().start()),
+                    ConvarKind::Choice(names) => ConvarValue::Int(names.len() as i64 - 1),
+                };
+                profile.values.insert(spec.name.to_string(), changed);
+            }
+            let mut document = kv3::Document::new(kv3::Object::new());
+            apply::set_convars(&mut document, &profile.convars(category)).unwrap();
+            let read = ConvarProfile::from_document(category, &kv3::parse(&kv3::write(&document)).unwrap(), "test".to_string());
+            assert_eq!(read, profile, "{}", category.id);
+        }
+    }
+
+    #[test]
+    fn parse_clamps_to_the_allowed_range() {
+        assert_eq!(HUD[0].parse("2.0"), Some(ConvarValue::Float(0.95)));
+        assert_eq!(HUD[1].parse("42"), Some(ConvarValue::Int(10)));
+        assert_eq!(RADAR[3].parse("0"), Some(ConvarValue::Bool(false)));
+    }
+
+    #[test]
+    fn loading_drops_unknown_and_mistyped_values() {
+        let json = r#"{"name": "hand edited", "values": {
+            "hud_scaling": {"Float": 0.8},
+            "hud_scalling": {"Float": 0.9},
+            "cl_showloadout": {"Float": 1.0},
+            "cl_hud_color": {"Int": 3}
+        }}"#;
+        let mut profile: ConvarProfile = serde_json::from_str(json).unwrap();
+        profile.retain_known(&CATEGORIES[0]);
+        let names: Vec<&str> = profile.values.keys().map(String::as_str).collect();
+        assert_eq!(names, ["cl_hud_color", "hud_scaling"]);
+        assert_eq!(profile.value(&HUD[3]), HUD[3].default());
+    }
+}
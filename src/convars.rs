@@ -0,0 +1,329 @@
+//! Built-in metadata about CS2 convars, used to explain, validate and group settings.
+
+use std::ops::RangeInclusive;
+
+/// Bump whenever entries are added, removed or changed.
+pub const DATABASE_VERSION: u32 = 1;
+/// The game build the table was last checked against.
+pub const GAME_VERSION: &str = "CS2 1.40";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConvarCategory {
+    Crosshair,
+    Viewmodel,
+    Mouse,
+    Hud,
+    Radar,
+    Audio,
+    Gameplay,
+    Network,
+    Video,
+}
+
+impl ConvarCategory {
+    pub const ALL: [ConvarCategory; 9] = [
+        ConvarCategory::Crosshair, ConvarCategory::Viewmodel, ConvarCategory::Mouse, ConvarCategory::Hud, ConvarCategory::Radar,
+        ConvarCategory::Audio, ConvarCategory::Gameplay, ConvarCategory::Network, ConvarCategory::Video,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ConvarCategory::Crosshair => "Crosshair",
+            ConvarCategory::Viewmodel => "Viewmodel",
+            ConvarCategory::Mouse => "Mouse",
+            ConvarCategory::Hud => "HUD",
+            ConvarCategory::Radar => "Radar",
+            ConvarCategory::Audio => "Audio",
+            ConvarCategory::Gameplay => "Gameplay",
+            ConvarCategory::Network => "Network",
+            ConvarCategory::Video => "Video",
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.label().eq_ignore_ascii_case(text))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvarType {
+    Bool,
+    Int,
+    Float,
+    String,
+}
+
+impl ConvarType {
+    pub fn label(self) -> &'static str {
+        match self {
+            ConvarType::Bool => "bool",
+            ConvarType::Int => "int",
+            ConvarType::Float => "float",
+            ConvarType::String => "string",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConvarInfo {
+    pub name: &'static str,
+    pub category: ConvarCategory,
+    pub kind: ConvarType,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub default: &'static str,
+    pub description: &'static str,
+    /// Set for CS:GO convars that no longer exist in CS2, with what replaced them.
+    pub removed: Option<&'static str>,
+}
+
+impl ConvarInfo {
+    /// Problem with `value` for this convar: a wrong type or a value the game clamps.
+    pub fn check(&self, value: &str) -> Option<String> {
+        let value = value.trim();
+        match self.kind {
+            ConvarType::String => None,
+            ConvarType::Bool if matches!(value, "true" | "false" | "0" | "1") => None,
+            ConvarType::Bool => Some(format!("expects true or false, got \"{}\"", value)),
+            ConvarType::Int | ConvarType::Float => {
+                let Ok(number) = value.parse::<f32>() else { return Some(format!("expects a number, got \"{}\"", value)) };
+                if self.kind == ConvarType::Int && number.fract() != 0.0 {
+                    return Some(format!("expects a whole number, got {}", value));
+                }
+                match (self.min, self.max) {
+                    (Some(min), _) if number < min => Some(format!("{} is below the minimum {}", value, min)),
+                    (_, Some(max)) if number > max => Some(format!("{} is above the maximum {}", value, max)),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// `value` clamped into the allowed range, `None` if it is fine or not a number.
+    pub fn clamp(&self, value: &str) -> Option<String> {
+        if !matches!(self.kind, ConvarType::Int | ConvarType::Float) { return None; }
+        let number = value.trim().parse::<f32>().ok()?;
+        let range = self.range();
+        let clamped = number.clamp(*range.start(), *range.end());
+        if clamped == number { return None; }
+        Some(match self.kind {
+            ConvarType::Int => format!("{}", clamped.round() as i64),
+            _ => format!("{:.6}", clamped),
+        })
+    }
+
+    /// Allowed values, unbounded ends reach to the limits of `f32`.
+    pub fn range(&self) -> RangeInclusive<f32> {
+        self.min.unwrap_or(f32::MIN)..=self.max.unwrap_or(f32::MAX)
+    }
+
+    pub fn range_label(&self) -> Option<String> {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => Some(format!("{} to {}", min, max)),
+            (Some(min), None) => Some(format!("at least {}", min)),
+            (None, Some(max)) => Some(format!("at most {}", max)),
+            (None, None) => None,
+        }
+    }
+
+    /// Multi-line explanation for tooltips and the command line.
+    pub fn describe(&self) -> String {
+        let mut text = format!("{} ({}, {})\n{}", self.name, self.category.label(), self.kind.label(), self.description);
+        if let Some(reason) = self.removed {
+            text.push_str(&format!("\nRemoved in CS2: {}", reason));
+            return text;
+        }
+        if let Some(range) = self.range_label() {
+            text.push_str(&format!("\nRange: {}", range));
+        }
+        text.push_str(&format!("\nDefault: {}", self.default));
+        text
+    }
+}
+
+const fn entry(name: &'static str, category: ConvarCategory, kind: ConvarType, min: Option<f32>, max: Option<f32>, default: &'static str, description: &'static str) -> ConvarInfo {
+    ConvarInfo { name, category, kind, min, max, default, description, removed: None }
+}
+
+const fn float(name: &'static str, category: ConvarCategory, min: f32, max: f32, default: &'static str, description: &'static str) -> ConvarInfo {
+    entry(name, category, ConvarType::Float, Some(min), Some(max), default, description)
+}
+
+const fn int(name: &'static str, category: ConvarCategory, min: f32, max: f32, default: &'static str, description: &'static str) -> ConvarInfo {
+    entry(name, category, ConvarType::Int, Some(min), Some(max), default, description)
+}
+
+const fn flag(name: &'static str, category: ConvarCategory, default: &'static str, description: &'static str) -> ConvarInfo {
+    entry(name, category, ConvarType::Bool, None, None, default, description)
+}
+
+const fn removed(name: &'static str, category: ConvarCategory, reason: &'static str) -> ConvarInfo {
+    ConvarInfo { name, category, kind: ConvarType::String, min: None, max: None, default: "", description: "CS:GO convar", removed: Some(reason) }
+}
+
+use ConvarCategory::*;
+
+pub const CONVARS: &[ConvarInfo] = &[
+    float("cl_crosshairgap", Crosshair, -10.0, 10.0, "1.000000", "Space between the crosshair arms and the center"),
+    float("cl_crosshair_outlinethickness", Crosshair, 0.0, 3.0, "1.000000", "Thickness of the black outline around the crosshair"),
+    int("cl_crosshaircolor_r", Crosshair, 0.0, 255.0, "50", "Red component of the custom crosshair color"),
+    int("cl_crosshaircolor_g", Crosshair, 0.0, 255.0, "250", "Green component of the custom crosshair color"),
+    int("cl_crosshaircolor_b", Crosshair, 0.0, 255.0, "50", "Blue component of the custom crosshair color"),
+    int("cl_crosshairalpha", Crosshair, 0.0, 255.0, "200", "Crosshair opacity, used when cl_crosshairusealpha is on"),
+    int("cl_crosshair_dynamic_splitdist", Crosshair, 0.0, 100.0, "7", "Distance the split crosshair arms move apart while moving"),
+    flag("cl_crosshair_recoil", Crosshair, "false", "Move the crosshair with the weapon's recoil"),
+    float("cl_fixedcrosshairgap", Crosshair, -10.0, 10.0, "3.000000", "Gap used by styles that take the gap from the weapon"),
+    int("cl_crosshaircolor", Crosshair, 0.0, 5.0, "1", "Color preset: 0 red, 1 green, 2 yellow, 3 blue, 4 cyan, 5 custom RGB"),
+    flag("cl_crosshair_drawoutline", Crosshair, "false", "Draw an outline around the crosshair"),
+    float("cl_crosshair_dynamic_splitalpha_innermod", Crosshair, 0.0, 1.0, "1.000000", "Opacity of the inner split crosshair parts"),
+    float("cl_crosshair_dynamic_splitalpha_outermod", Crosshair, 0.3, 1.0, "0.500000", "Opacity of the outer split crosshair parts"),
+    float("cl_crosshair_dynamic_maxdist_splitratio", Crosshair, 0.0, 1.0, "0.350000", "Length ratio between the inner and outer split parts"),
+    float("cl_crosshairthickness", Crosshair, 0.0, 6.0, "0.500000", "Thickness of the crosshair arms"),
+    int("cl_crosshairstyle", Crosshair, 0.0, 5.0, "2", "Style: 0 default, 1 default static, 2 classic, 3 classic dynamic, 4 classic static, 5 legacy"),
+    flag("cl_crosshairdot", Crosshair, "false", "Draw a dot in the center"),
+    flag("cl_crosshairgap_useweaponvalue", Crosshair, "false", "Take the gap from the weapon instead of cl_crosshairgap"),
+    flag("cl_crosshairusealpha", Crosshair, "true", "Apply cl_crosshairalpha to the crosshair"),
+    flag("cl_crosshair_t", Crosshair, "false", "Hide the top arm for a T shaped crosshair"),
+    float("cl_crosshairsize", Crosshair, 0.0, 100.0, "5.000000", "Length of the crosshair arms"),
+    removed("cl_crosshairscale", Crosshair, "crosshairs always scale with the vertical resolution"),
+
+    float("viewmodel_fov", Viewmodel, 54.0, 68.0, "60.000000", "Field of view of the weapon model"),
+    float("viewmodel_offset_x", Viewmodel, -2.5, 2.5, "1.000000", "Sideways weapon position"),
+    float("viewmodel_offset_y", Viewmodel, -2.0, 2.0, "1.000000", "Forward weapon position"),
+    float("viewmodel_offset_z", Viewmodel, -2.0, 2.0, "-1.000000", "Vertical weapon position"),
+    int("viewmodel_presetpos", Viewmodel, 0.0, 3.0, "1", "Weapon position preset: 1 desktop, 2 couch, 3 classic, 0 custom"),
+    flag("cl_righthand", Viewmodel, "true", "Hold the weapon in the right hand"),
+    removed("cl_bob_lower_amt", Viewmodel, "weapon bobbing can no longer be changed"),
+    removed("cl_bobamt_lat", Viewmodel, "weapon bobbing can no longer be changed"),
+    removed("cl_bobamt_vert", Viewmodel, "weapon bobbing can no longer be changed"),
+    removed("cl_bobcycle", Viewmodel, "weapon bobbing can no longer be changed"),
+
+    float("sensitivity", Mouse, 0.0001, 8.0, "1.250000", "Mouse sensitivity"),
+    float("zoom_sensitivity_ratio", Mouse, 0.0, 2.0, "1.000000", "Sensitivity multiplier while zoomed with a scope"),
+    float("m_yaw", Mouse, 0.0, 1.0, "0.022000", "Degrees turned per mouse count horizontally at sensitivity 1"),
+    float("m_pitch", Mouse, 0.0, 1.0, "0.022000", "Degrees turned per mouse count vertically at sensitivity 1"),
+    removed("m_rawinput", Mouse, "raw input is always used"),
+    removed("m_customaccel", Mouse, "mouse acceleration was removed"),
+    removed("m_mouseaccel1", Mouse, "mouse acceleration was removed"),
+    removed("m_mouseaccel2", Mouse, "mouse acceleration was removed"),
+
+    float("hud_scaling", Hud, 0.5, 0.95, "0.850000", "Size of the HUD"),
+    int("cl_hud_color", Hud, 0.0, 10.0, "0", "HUD color preset"),
+    float("cl_hud_background_alpha", Hud, 0.0, 1.0, "0.500000", "Opacity of HUD backgrounds"),
+    flag("cl_showloadout", Hud, "true", "Always show the weapon inventory"),
+    flag("cl_showfps", Hud, "false", "Show the frame rate in the corner"),
+    removed("net_graph", Hud, "use cl_showfps or the telemetry options of the settings menu"),
+
+    float("cl_radar_scale", Radar, 0.25, 1.0, "0.700000", "Zoom of the map shown on the radar"),
+    float("cl_hud_radar_scale", Radar, 0.8, 1.3, "1.000000", "Size of the radar"),
+    float("cl_radar_icon_scale_min", Radar, 0.4, 1.0, "0.600000", "Minimum size of player icons on the radar"),
+    flag("cl_radar_always_centered", Radar, "true", "Keep the player in the center of the radar"),
+    flag("cl_radar_rotate", Radar, "true", "Rotate the radar with the view direction"),
+    flag("cl_radar_square_with_scoreboard", Radar, "true", "Show a square radar while the scoreboard is open"),
+
+    float("volume", Audio, 0.0, 1.0, "1.000000", "Master volume"),
+    float("snd_voipvolume", Audio, 0.0, 1.0, "1.000000", "Voice chat volume"),
+    float("snd_musicvolume", Audio, 0.0, 1.0, "1.000000", "Music volume"),
+    float("snd_menumusic_volume", Audio, 0.0, 1.0, "1.000000", "Main menu music volume"),
+    float("snd_roundstart_volume", Audio, 0.0, 1.0, "1.000000", "Round start music volume"),
+    float("snd_roundend_volume", Audio, 0.0, 1.0, "1.000000", "Round end music volume"),
+    float("snd_tensecondwarning_volume", Audio, 0.0, 1.0, "1.000000", "Ten second bomb and round warning volume"),
+    float("snd_mvp_volume", Audio, 0.0, 1.0, "1.000000", "MVP anthem volume"),
+    float("snd_deathcamera_volume", Audio, 0.0, 1.0, "1.000000", "Death camera music volume"),
+    flag("voice_enable", Audio, "true", "Hear voice chat"),
+
+    flag("cl_autowepswitch", Gameplay, "true", "Switch to a picked up weapon automatically"),
+    flag("cl_use_opens_buy_menu", Gameplay, "true", "Open the buy menu with the use key in the buy zone"),
+    flag("cl_sniper_delay_unscope", Gameplay, "false", "Stay scoped in until the shot has been fired"),
+    flag("cl_dm_buyrandomweapons", Gameplay, "true", "Get random weapons in deathmatch"),
+    removed("cl_forcepreload", Gameplay, "the game always preloads what it needs"),
+
+    int("rate", Network, 98304.0, 1000000.0, "786432", "Maximum bytes per second the server may send"),
+    removed("cl_updaterate", Network, "replaced by sub-tick updates"),
+    removed("cl_cmdrate", Network, "replaced by sub-tick updates"),
+    removed("cl_interp", Network, "interpolation is handled by the engine"),
+    removed("cl_interp_ratio", Network, "interpolation is handled by the engine"),
+
+    int("fps_max", Video, 0.0, 1000.0, "400", "Frame rate limit, 0 for unlimited"),
+    float("r_fullscreen_gamma", Video, 1.6, 2.6, "2.200000", "Brightness in fullscreen mode"),
+    removed("mat_queue_mode", Video, "the renderer is always multithreaded"),
+];
+
+/// Metadata for `name`, compared case insensitively.
+pub fn lookup(name: &str) -> Option<&'static ConvarInfo> {
+    CONVARS.iter().find(|info| info.name.eq_ignore_ascii_case(name))
+}
+
+/// Entries whose name or description contains `query`, optionally limited to one category.
+pub fn search(query: &str, category: Option<ConvarCategory>) -> Vec<&'static ConvarInfo> {
+    let query = query.to_lowercase();
+    CONVARS.iter()
+        .filter(|info| category.is_none_or(|c| info.category == c))
+        .filter(|info| info.name.contains(&query) || info.description.to_lowercase().contains(&query))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{compare, convar_profiles, sensitivity, share_code, viewmodel};
+
+    #[test]
+    fn every_convar_cs2man_writes_is_known() {
+        let profile = share_code::sample_profile();
+        let mut written: Vec<&str> = compare::fields(&profile).into_iter().map(|(name, _)| name).collect();
+        written.extend(viewmodel::ViewmodelProfile::default().convars().into_iter().map(|(name, _)| name));
+        written.extend(["sensitivity", "zoom_sensitivity_ratio", "m_yaw", "m_pitch"]);
+        written.extend(convar_profiles::CATEGORIES.iter().flat_map(|c| c.convars.iter().map(|spec| spec.name)));
+        for name in written {
+            let info = lookup(name).unwrap_or_else(|| panic!("{} is missing", name));
+            assert!(info.removed.is_none(), "{} is marked removed", name);
+        }
+        let mut names: Vec<&str> = CONVARS.iter().map(|info| info.name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), CONVARS.len());
+    }
+
+    #[test]
+    fn defaults_and_kinds_agree_with_the_database() {
+        let same = |info: &ConvarInfo, value: &str| match info.kind {
+            ConvarType::Bool => matches!(value, "true" | "1") == matches!(info.default, "true" | "1"),
+            _ => value.parse::<f32>().ok() == info.default.parse::<f32>().ok(),
+        };
+        let defaults = viewmodel::ViewmodelProfile::default().convars().into_iter()
+            .chain(sensitivity::Sensitivity::default().convars());
+        for (name, value) in defaults {
+            let info = lookup(name).unwrap();
+            assert_eq!(info.check(&value), None, "{}", name);
+            assert!(same(info, &value), "{} default {} differs from {}", name, value, info.default);
+        }
+        for category in &convar_profiles::CATEGORIES {
+            for spec in category.convars {
+                let info = spec.info();
+                let expected = match spec.kind {
+                    convar_profiles::ConvarKind::Bool => ConvarType::Bool,
+                    convar_profiles::ConvarKind::Float { .. } => ConvarType::Float,
+                    convar_profiles::ConvarKind::Choice(names) => {
+                        assert_eq!(info.range(), 0.0..=(names.len() - 1) as f32, "{}", spec.name);
+                        ConvarType::Int
+                    }
+                };
+                assert_eq!(info.kind, expected, "{}", spec.name);
+                assert!(same(info, &spec.default().format()), "{}", spec.name);
+            }
+        }
+    }
+
+    #[test]
+    fn checks_and_clamps_values() {
+        let size = lookup("CL_CROSSHAIRSIZE").unwrap();
+        assert_eq!(size.check("3.5"), None);
+        assert!(size.check("150").unwrap().contains("maximum"));
+        assert_eq!(size.clamp("150"), Some("100.000000".to_string()));
+        let style = lookup("cl_crosshairstyle").unwrap();
+        assert!(style.check("2.5").is_some());
+        assert_eq!(style.clamp("9"), Some("5".to_string()));
+        assert!(lookup("cl_crosshair_t").unwrap().check("maybe").is_some());
+    }
+}
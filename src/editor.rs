@@ -0,0 +1,313 @@
+use std::ops::RangeInclusive;
+
+use eframe::egui;
+
+use crate::render;
+use crate::CrosshairProfile;
+
+/// `cl_crosshairstyle` values as named in the game's settings menu.
+pub const STYLE_NAMES: [&str; 6] = ["Default", "Default Static", "Classic", "Classic Dynamic", "Classic Static", "Legacy"];
+/// `cl_crosshaircolor` presets; only "Custom" uses the RGB values.
+pub const COLOR_NAMES: [&str; 6] = ["Red", "Green", "Yellow", "Blue", "Cyan", "Custom"];
+pub const CUSTOM_COLOR: u8 = 5;
+
+/// Float settings with the step and range the share code can store, and the range the
+/// game accepts. Values outside the game range are clamped when the crosshair is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatField {
+    Size,
+    Thickness,
+    Gap,
+    FixedGap,
+    OutlineThickness,
+    SplitAlphaInner,
+    SplitAlphaOuter,
+    SplitRatio,
+}
+
+impl FloatField {
+    pub fn label(self) -> &'static str {
+        match self {
+            FloatField::Size => "Length",
+            FloatField::Thickness => "Thickness",
+            FloatField::Gap => "Gap",
+            FloatField::FixedGap => "Fixed gap",
+            FloatField::OutlineThickness => "Outline thickness",
+            FloatField::SplitAlphaInner => "Inner split alpha",
+            FloatField::SplitAlphaOuter => "Outer split alpha",
+            FloatField::SplitRatio => "Split size ratio",
+        }
+    }
+
+    pub fn get(self, p: &CrosshairProfile) -> f32 {
+        match self {
+            FloatField::Size => p.size,
+            FloatField::Thickness => p.thickness,
+            FloatField::Gap => p.gap,
+            FloatField::FixedGap => p.fixed_gap,
+            FloatField::OutlineThickness => p.outline_thickness,
+            FloatField::SplitAlphaInner => p.dynamic_splitalpha_innermod,
+            FloatField::SplitAlphaOuter => p.dynamic_splitalpha_outermod,
+            FloatField::SplitRatio => p.dynamic_maxdist_split_ratio,
+        }
+    }
+
+    pub fn get_mut(self, p: &mut CrosshairProfile) -> &mut f32 {
+        match self {
+            FloatField::Size => &mut p.size,
+            FloatField::Thickness => &mut p.thickness,
+            FloatField::Gap => &mut p.gap,
+            FloatField::FixedGap => &mut p.fixed_gap,
+            FloatField::OutlineThickness => &mut p.outline_thickness,
+            FloatField::SplitAlphaInner => &mut p.dynamic_splitalpha_innermod,
+            FloatField::SplitAlphaOuter => &mut p.dynamic_splitalpha_outermod,
+            FloatField::SplitRatio => &mut p.dynamic_maxdist_split_ratio,
+        }
+    }
+
+    /// Smallest change the share code can represent.
+    pub fn step(self) -> f32 {
+        match self {
+            FloatField::OutlineThickness => 0.5,
+            _ => 0.1,
+        }
+    }
+
+    pub fn codec_range(self) -> RangeInclusive<f32> {
+        match self {
+            FloatField::Size => 0.0..=819.1,
+            FloatField::Thickness => 0.0..=25.5,
+            FloatField::Gap | FloatField::FixedGap => -12.8..=12.7,
+            FloatField::OutlineThickness => 0.0..=127.5,
+            FloatField::SplitAlphaInner | FloatField::SplitAlphaOuter | FloatField::SplitRatio => 0.0..=1.5,
+        }
+    }
+
+    pub fn game_range(self) -> RangeInclusive<f32> {
+        match self {
+            FloatField::Size => 0.0..=100.0,
+            FloatField::Thickness => 0.0..=6.0,
+            FloatField::Gap | FloatField::FixedGap => -10.0..=10.0,
+            FloatField::OutlineThickness => 0.0..=3.0,
+            FloatField::SplitAlphaInner | FloatField::SplitRatio => 0.0..=1.0,
+            FloatField::SplitAlphaOuter => 0.3..=1.0,
+        }
+    }
+
+    /// The gap field the crosshair is drawn with, see [`render::uses_fixed_gap`].
+    pub fn gap(profile: &CrosshairProfile) -> FloatField {
+        if render::uses_fixed_gap(profile) { FloatField::FixedGap } else { FloatField::Gap }
+    }
+
+    /// Sets the field and drops the profile's original share code, which no longer matches.
+    pub fn set(self, p: &mut CrosshairProfile, value: f32) {
+        *self.get_mut(p) = value;
+        p.original_code = None;
+    }
+
+    /// Rounds to the nearest encodable step inside the codec range.
+    pub fn snap(self, value: f32) -> f32 {
+        let range = self.codec_range();
+        let step = self.step();
+        ((value / step).round() * step).clamp(*range.start(), *range.end())
+    }
+}
+
+/// Problems with values the game would clamp or reject.
+pub fn warnings(profile: &CrosshairProfile) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let fields = [
+        FloatField::Size, FloatField::Thickness, FloatField::Gap, FloatField::FixedGap, FloatField::OutlineThickness,
+        FloatField::SplitAlphaInner, FloatField::SplitAlphaOuter, FloatField::SplitRatio,
+    ];
+    for field in fields {
+        let value = field.get(profile);
+        let range = field.game_range();
+        if !range.contains(&value) {
+            warnings.push(format!("{} {} is outside the game's range {}..{} and will be clamped", field.label(), value, range.start(), range.end()));
+        }
+    }
+    if profile.style as usize >= STYLE_NAMES.len() {
+        warnings.push(format!("Style {} does not exist in the game", profile.style));
+    }
+    if profile.color > CUSTOM_COLOR {
+        warnings.push(format!("Color {} does not exist in the game", profile.color));
+    }
+    warnings
+}
+
+fn float_input(ui: &mut egui::Ui, profile: &mut CrosshairProfile, field: FloatField) -> bool {
+    let range = field.codec_range();
+    let value = field.get_mut(profile);
+    ui.label(field.label());
+    let response = ui.add(egui::DragValue::new(value)
+        .speed(field.step())
+        .range(range)
+        .fixed_decimals(if field.step() < 0.5 { 1 } else { 2 }));
+    // Values loaded off-step stay as they are until the user edits them
+    if response.changed() {
+        *value = field.snap(*value);
+    }
+    ui.end_row();
+    response.changed()
+}
+
+fn named_combo(ui: &mut egui::Ui, id: &str, label: &str, value: &mut u8, names: &[&str]) -> bool {
+    let mut changed = false;
+    ui.label(label);
+    egui::ComboBox::from_id_source(id)
+        .selected_text(names.get(*value as usize).copied().unwrap_or("Unknown"))
+        .show_ui(ui, |ui| {
+            for (i, name) in names.iter().enumerate() {
+                changed |= ui.selectable_value(value, i as u8, *name).changed();
+            }
+        });
+    ui.end_row();
+    changed
+}
+
+/// Grouped crosshair editor. Returns true if any setting changed this frame, in which case
+/// the profile's original share code is dropped.
+pub fn editor_ui(ui: &mut egui::Ui, profile: &mut CrosshairProfile) -> bool {
+    let mut changed = false;
+    ui.columns(2, |columns| {
+        columns[0].strong("Shape");
+        egui::Grid::new("editor_shape").num_columns(2).show(&mut columns[0], |ui| {
+            changed |= named_combo(ui, "editor_style", "Style", &mut profile.style, &STYLE_NAMES);
+            changed |= float_input(ui, profile, FloatField::Size);
+            changed |= float_input(ui, profile, FloatField::Thickness);
+            // Only one of the two gaps is drawn, show that one
+            changed |= float_input(ui, profile, FloatField::gap(profile));
+            ui.label("");
+            let default_style = render::is_default_style(profile.style);
+            changed |= ui.add_enabled(!default_style, egui::Checkbox::new(&mut profile.gap_use_weapon_value, "Use weapon gap"))
+                .on_disabled_hover_text("The default styles always use the fixed gap")
+                .changed();
+            ui.end_row();
+            ui.label("");
+            ui.horizontal(|ui| {
+                changed |= ui.checkbox(&mut profile.dot, "Dot").changed();
+                changed |= ui.checkbox(&mut profile.t, "T-Style").changed();
+                changed |= ui.checkbox(&mut profile.recoil, "Follow recoil").changed();
+            });
+            ui.end_row();
+        });
+
+        columns[1].strong("Color");
+        egui::Grid::new("editor_color").num_columns(2).show(&mut columns[1], |ui| {
+            changed |= named_combo(ui, "editor_color_preset", "Color", &mut profile.color, &COLOR_NAMES);
+            if profile.color == CUSTOM_COLOR {
+                ui.label("RGB");
+                let mut rgb = [profile.red, profile.green, profile.blue];
+                if egui::color_picker::color_edit_button_srgb(ui, &mut rgb).changed() {
+                    [profile.red, profile.green, profile.blue] = rgb;
+                    changed = true;
+                }
+                ui.end_row();
+            }
+            ui.label("");
+            changed |= ui.checkbox(&mut profile.use_alpha, "Use alpha").changed();
+            ui.end_row();
+            if profile.use_alpha {
+                ui.label("Alpha");
+                changed |= ui.add(egui::DragValue::new(&mut profile.alpha).range(0..=255)).changed();
+                ui.end_row();
+            }
+            ui.label("");
+            changed |= ui.checkbox(&mut profile.draw_outline, "Outline").changed();
+            ui.end_row();
+            if profile.draw_outline {
+                changed |= float_input(ui, profile, FloatField::OutlineThickness);
+            }
+        });
+
+        if render::is_split_style(profile.style) {
+            columns[1].strong("Split");
+            egui::Grid::new("editor_split").num_columns(2).show(&mut columns[1], |ui| {
+                ui.label("Split distance");
+                changed |= ui.add(egui::DragValue::new(&mut profile.dynamic_splitdist).range(0..=127)).changed();
+                ui.end_row();
+                changed |= float_input(ui, profile, FloatField::SplitAlphaInner);
+                changed |= float_input(ui, profile, FloatField::SplitAlphaOuter);
+                changed |= float_input(ui, profile, FloatField::SplitRatio);
+            });
+        }
+    });
+    if changed {
+        profile.original_code = None;
+    }
+    for warning in warnings(profile) {
+        ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", warning));
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::share_code;
+
+    #[test]
+    fn snap_rounds_to_the_step_and_clamps_to_the_codec_range() {
+        assert!((FloatField::Gap.snap(-2.04) - -2.0).abs() < 1e-5);
+        assert!((FloatField::Size.snap(3.25) - 3.3).abs() < 1e-5);
+        assert_eq!(FloatField::OutlineThickness.snap(1.3), 1.5);
+        assert_eq!(FloatField::OutlineThickness.snap(1.2), 1.0);
+        assert_eq!(FloatField::Gap.snap(-20.0), -12.8);
+        assert_eq!(FloatField::Thickness.snap(40.0), 25.5);
+    }
+
+    #[test]
+    fn warns_about_values_the_game_clamps() {
+        let mut profile = share_code::sample_profile();
+        assert!(warnings(&profile).is_empty(), "{:?}", warnings(&profile));
+        profile.size = 150.0;
+        profile.dynamic_splitalpha_outermod = 0.1;
+        profile.style = 7;
+        profile.color = 9;
+        let found = warnings(&profile);
+        assert_eq!(found.len(), 4, "{:?}", found);
+        assert!(found[0].starts_with("Length 150"));
+        assert!(found[1].starts_with("Outer split alpha 0.1"));
+        assert!(found[2].contains("Style 7"));
+        assert!(found[3].contains("Color 9"));
+    }
+
+    #[test]
+    fn editing_a_field_regenerates_the_code() {
+        let mut profile = share_code::sample_profile();
+        let original = profile.original_code.clone().unwrap();
+        assert_eq!(share_code::code(&profile), original);
+        FloatField::Gap.set(&mut profile, 1.0);
+        let code = share_code::code(&profile);
+        assert_ne!(code, original);
+        let decoded = share_code::profile_from_bytes(&share_code::decode_bytes(&code).unwrap(), "decoded".to_string(), &code);
+        assert_eq!(decoded.gap, 1.0);
+    }
+
+    #[test]
+    fn gap_field_follows_style_and_weapon_gap() {
+        let mut profile = share_code::sample_profile();
+        profile.gap_use_weapon_value = false;
+        for (style, field) in [(0, FloatField::FixedGap), (1, FloatField::FixedGap), (2, FloatField::Gap), (4, FloatField::Gap)] {
+            profile.style = style;
+            assert_eq!(FloatField::gap(&profile), field, "style {}", style);
+        }
+        profile.gap_use_weapon_value = true;
+        assert_eq!(FloatField::gap(&profile), FloatField::FixedGap);
+    }
+
+    #[test]
+    fn showing_an_off_step_value_is_not_an_edit() {
+        let mut profile = share_code::sample_profile();
+        profile.gap = 0.25;
+        profile.style = 4;
+        let mut changed = true;
+        let _ = egui::Context::default().run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| changed = editor_ui(ui, &mut profile));
+        });
+        assert!(!changed);
+        assert_eq!(profile.gap, 0.25);
+        assert!(profile.original_code.is_some());
+    }
+}
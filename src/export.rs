@@ -0,0 +1,159 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::raster::{self, RgbaBuffer};
+use crate::render::{self, Resolution};
+use crate::CrosshairProfile;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportBackground {
+    Transparent,
+    Solid([u8; 4]),
+    Image(PathBuf),
+}
+
+impl ExportBackground {
+    /// Parses the CLI form: `transparent`, `#rrggbb` or a path to an image file.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if value.eq_ignore_ascii_case("transparent") {
+            return Ok(ExportBackground::Transparent);
+        }
+        if let Some(hex) = value.strip_prefix('#') {
+            let channel = |i: usize| hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok());
+            return match (hex.len(), channel(0), channel(2), channel(4)) {
+                (6, Some(r), Some(g), Some(b)) => Ok(ExportBackground::Solid([r, g, b, 255])),
+                _ => Err(format!("Invalid color: {}", value)),
+            };
+        }
+        Ok(ExportBackground::Image(PathBuf::from(value)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportOptions {
+    pub resolution: Resolution,
+    /// Every game pixel becomes a `zoom` x `zoom` block in the PNG.
+    pub zoom: u32,
+    /// Width and height of the exported area in game pixels, around the screen center.
+    pub size: u32,
+    pub background: ExportBackground,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self { resolution: render::COMMON_RESOLUTIONS[0], zoom: 4, size: 64, background: ExportBackground::Transparent }
+    }
+}
+
+pub fn load_image(path: &Path) -> Result<RgbaBuffer, String> {
+    let image = image::open(path).map_err(|e| format!("Could not load {}: {}", path.display(), e))?.into_rgba8();
+    Ok(RgbaBuffer { width: image.width(), height: image.height(), pixels: image.into_raw() })
+}
+
+pub fn write_png(path: &Path, buffer: &RgbaBuffer) -> Result<(), String> {
+    image::save_buffer(path, &buffer.pixels, buffer.width, buffer.height, image::ColorType::Rgba8)
+        .map_err(|e| format!("Could not write {}: {}", path.display(), e))
+}
+
+pub fn scale_nearest(buffer: &RgbaBuffer, zoom: u32) -> RgbaBuffer {
+    let zoom = zoom.max(1);
+    let mut scaled = RgbaBuffer::new(buffer.width * zoom, buffer.height * zoom, [0, 0, 0, 0]);
+    for y in 0..scaled.height {
+        for x in 0..scaled.width {
+            let si = ((y / zoom) as usize * buffer.width as usize + (x / zoom) as usize) * 4;
+            let ti = (y as usize * scaled.width as usize + x as usize) * 4;
+            scaled.pixels[ti..ti + 4].copy_from_slice(&buffer.pixels[si..si + 4]);
+        }
+    }
+    scaled
+}
+
+/// The unscaled export area with the background drawn, shared by every profile of an export.
+fn background_buffer(options: &ExportOptions) -> Result<RgbaBuffer, String> {
+    Ok(match &options.background {
+        ExportBackground::Transparent => RgbaBuffer::new(options.size, options.size, [0, 0, 0, 0]),
+        ExportBackground::Solid(rgba) => RgbaBuffer::new(options.size, options.size, *rgba),
+        ExportBackground::Image(path) => {
+            let mut buffer = RgbaBuffer::new(options.size, options.size, [0, 0, 0, 0]);
+            raster::draw_background(&mut buffer, &load_image(path)?, [0.0, 0.0], 1.0);
+            buffer
+        }
+    })
+}
+
+fn render_on(background: &RgbaBuffer, profile: &CrosshairProfile, options: &ExportOptions) -> RgbaBuffer {
+    let mut buffer = background.clone();
+    raster::draw_crosshair(&mut buffer, profile, options.resolution, 0.0);
+    scale_nearest(&buffer, options.zoom)
+}
+
+pub fn render_export(profile: &CrosshairProfile, options: &ExportOptions) -> Result<RgbaBuffer, String> {
+    Ok(render_on(&background_buffer(options)?, profile, options))
+}
+
+/// Turns a profile name into a safe file name without extension.
+pub fn file_stem(name: &str) -> String {
+    let stem: String = name.trim().chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | ' ' | '.') { c } else { '_' })
+        .collect();
+    let stem = stem.trim_matches(|c: char| c == '.' || c == ' ').to_string();
+    if stem.is_empty() { "crosshair".to_string() } else { stem }
+}
+
+pub fn export_profile(profile: &CrosshairProfile, path: &Path, options: &ExportOptions) -> Result<(), String> {
+    write_png(path, &render_export(profile, options)?)
+}
+
+/// Renders every profile into `dir` as `<name>.png`, numbering repeated names.
+pub fn export_library(library: &[CrosshairProfile], dir: &Path, options: &ExportOptions) -> Result<Vec<PathBuf>, String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let background = background_buffer(options)?;
+    let mut written: Vec<PathBuf> = Vec::new();
+    for profile in library {
+        let stem = file_stem(&profile.name);
+        let mut path = dir.join(format!("{}.png", stem));
+        let mut n = 2;
+        while written.contains(&path) {
+            path = dir.join(format!("{} ({}).png", stem, n));
+            n += 1;
+        }
+        write_png(&path, &render_on(&background, profile, options))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::share_code;
+
+    #[test]
+    fn parses_background_options() {
+        assert_eq!(ExportBackground::parse("Transparent"), Ok(ExportBackground::Transparent));
+        assert_eq!(ExportBackground::parse("#1a2B3c"), Ok(ExportBackground::Solid([0x1a, 0x2b, 0x3c, 255])));
+        assert!(ExportBackground::parse("#12345").is_err());
+        assert!(ExportBackground::parse("#12345g").is_err());
+        assert_eq!(ExportBackground::parse("maps/dust2.png"), Ok(ExportBackground::Image(PathBuf::from("maps/dust2.png"))));
+    }
+
+    #[test]
+    fn file_stem_replaces_unsafe_characters() {
+        assert_eq!(file_stem("  NaVi/s1mple: AWP  "), "NaVi_s1mple_ AWP");
+        assert_eq!(file_stem("..hidden."), "hidden");
+        assert_eq!(file_stem(" . "), "crosshair");
+        assert_eq!(file_stem("ZywOo v2.1"), "ZywOo v2.1");
+    }
+
+    #[test]
+    fn export_library_numbers_repeated_names() {
+        let dir = std::env::temp_dir().join(format!("cs2man_export_{}", std::process::id()));
+        let library = vec![share_code::sample_profile(), share_code::sample_profile(), CrosshairProfile { name: "device?".to_string(), ..share_code::sample_profile() }];
+        let options = ExportOptions { size: 16, zoom: 1, ..ExportOptions::default() };
+        let written = export_library(&library, &dir, &options).unwrap();
+        let names: Vec<String> = written.iter().map(|p| p.file_name().unwrap().to_string_lossy().into_owned()).collect();
+        assert_eq!(names, ["device.png", "device (2).png", "device_.png"]);
+        assert!(written.iter().all(|p| p.exists()));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,154 @@
+use std::time::{Duration, Instant};
+
+use crate::CrosshairProfile;
+
+/// Editor and library state restored by undo and redo.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub active_profile: CrosshairProfile,
+    pub library: Vec<CrosshairProfile>,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    label: String,
+    /// Library index a continuous edit applies to, `None` for the active profile.
+    target: Option<usize>,
+    /// Only continuous edits are extended by later edits.
+    continuous: bool,
+    /// State before the edit on the undo stack, state before undoing on the redo stack.
+    snapshot: Snapshot,
+    at: Instant,
+}
+
+/// Continuous edits with the same label and target closer than this (one slider drag,
+/// typing a name) are merged into a single undo step.
+const COALESCE_WINDOW: Duration = Duration::from_millis(800);
+const MAX_ENTRIES: usize = 200;
+
+/// Undo and redo stacks for the current session.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Entry>,
+    redo: Vec<Entry>,
+}
+
+impl History {
+    /// Records `before` as the state preceding the edit described by `label`.
+    pub fn record(&mut self, label: impl Into<String>, before: Snapshot) {
+        self.push(Entry { label: label.into(), target: None, continuous: false, snapshot: before, at: Instant::now() });
+    }
+
+    /// Like [`History::record`], but extends the previous step if it edited the same
+    /// `target` with the same label within the coalesce window. `before` is only called
+    /// when a new step starts, so a drag does not clone the library every frame.
+    pub fn record_continuous(&mut self, label: &str, target: Option<usize>, before: impl FnOnce() -> Snapshot) {
+        if let Some(last) = self.undo.last_mut() {
+            if last.continuous && last.label == label && last.target == target && last.at.elapsed() < COALESCE_WINDOW {
+                last.at = Instant::now();
+                self.redo.clear();
+                return;
+            }
+        }
+        self.push(Entry { label: label.to_string(), target, continuous: true, snapshot: before(), at: Instant::now() });
+    }
+
+    fn push(&mut self, entry: Entry) {
+        self.redo.clear();
+        self.undo.push(entry);
+        if self.undo.len() > MAX_ENTRIES {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Returns the state to restore, `current` becomes redoable.
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let entry = self.undo.pop()?;
+        self.redo.push(Entry { snapshot: current, continuous: false, at: Instant::now(), ..entry });
+        Some(entry.snapshot)
+    }
+
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let entry = self.redo.pop()?;
+        self.undo.push(Entry { snapshot: current, continuous: false, at: Instant::now(), ..entry });
+        Some(entry.snapshot)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Labels of undoable edits, most recent first.
+    pub fn undo_labels(&self) -> impl Iterator<Item = &str> {
+        self.undo.iter().rev().map(|e| e.label.as_str())
+    }
+
+    /// Labels of redoable edits, next redo first.
+    pub fn redo_labels(&self) -> impl Iterator<Item = &str> {
+        self.redo.iter().rev().map(|e| e.label.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::share_code;
+
+    fn snapshot(gap: f32) -> Snapshot {
+        let mut profile = share_code::sample_profile();
+        profile.gap = gap;
+        Snapshot { active_profile: profile, library: Vec::new() }
+    }
+
+    #[test]
+    fn undo_and_redo_walk_the_recorded_states() {
+        let mut history = History::default();
+        assert!(!history.can_undo() && !history.can_redo());
+        history.record("first", snapshot(0.0));
+        history.record("second", snapshot(1.0));
+        assert_eq!(history.undo_labels().collect::<Vec<_>>(), vec!["second", "first"]);
+
+        assert_eq!(history.undo(snapshot(2.0)).unwrap().active_profile.gap, 1.0);
+        assert_eq!(history.undo(snapshot(1.0)).unwrap().active_profile.gap, 0.0);
+        assert!(history.undo(snapshot(0.0)).is_none());
+        assert_eq!(history.redo_labels().collect::<Vec<_>>(), vec!["first", "second"]);
+        assert_eq!(history.redo(snapshot(0.0)).unwrap().active_profile.gap, 1.0);
+
+        history.record("third", snapshot(1.0));
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn continuous_edits_coalesce_per_label_and_target() {
+        let mut history = History::default();
+        let taken = Cell::new(0);
+        let before = |gap: f32| {
+            taken.set(taken.get() + 1);
+            snapshot(gap)
+        };
+        history.record_continuous("Rename crosshair", Some(0), || before(0.0));
+        history.record_continuous("Rename crosshair", Some(0), || before(1.0));
+        assert_eq!((history.undo_labels().count(), taken.get()), (1, 1));
+
+        history.record_continuous("Rename crosshair", Some(1), || before(2.0));
+        history.record_continuous("Edit active profile", None, || before(3.0));
+        history.record_continuous("Edit active profile", None, || before(4.0));
+        assert_eq!((history.undo_labels().count(), taken.get()), (3, 3));
+
+        history.record("Delete crosshair", snapshot(5.0));
+        history.record_continuous("Edit active profile", None, || before(6.0));
+        assert_eq!(history.undo_labels().count(), 5);
+
+        // An undone step is not extended after redo
+        let current = history.undo(snapshot(7.0)).unwrap();
+        history.redo(current);
+        history.record_continuous("Edit active profile", None, || before(8.0));
+        assert_eq!(history.undo_labels().count(), 6);
+    }
+}
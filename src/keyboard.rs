@@ -0,0 +1,176 @@
+use std::sync::OnceLock;
+
+use eframe::egui;
+
+use crate::binds::BindSet;
+
+/// Width of the drawn layout in key units, keyboard plus navigation block plus mouse.
+const LAYOUT_WIDTH: f32 = 22.75;
+const LAYOUT_HEIGHT: f32 = 6.5;
+const KEY_GAP: f32 = 0.08;
+
+const BOUND: egui::Color32 = egui::Color32::from_rgb(60, 110, 170);
+const DIFFERS: egui::Color32 = egui::Color32::from_rgb(200, 120, 40);
+const SELECTED: egui::Color32 = egui::Color32::from_rgb(240, 210, 60);
+
+/// One key cap: the game's key name, its label and its place in key units.
+struct Key {
+    name: &'static str,
+    label: &'static str,
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+/// Keys placed left to right starting at `x`, each `(name, label, width)`.
+fn row(keys: &mut Vec<Key>, mut x: f32, y: f32, caps: &[(&'static str, &'static str, f32)]) {
+    for &(name, label, w) in caps {
+        keys.push(Key { name, label, x, y, w, h: 1.0 });
+        x += w;
+    }
+}
+
+fn letters(names: &'static str) -> Vec<(&'static str, &'static str, f32)> {
+    (0..names.len()).map(|i| (&names[i..i + 1], &names[i..i + 1], 1.0)).collect()
+}
+
+/// US layout with the key names the game uses in `cs2_user_keys` files, built once.
+fn layout() -> &'static [Key] {
+    static LAYOUT: OnceLock<Vec<Key>> = OnceLock::new();
+    LAYOUT.get_or_init(build_layout)
+}
+
+fn build_layout() -> Vec<Key> {
+    let mut keys = Vec::new();
+    row(&mut keys, 0.0, 0.0, &[("ESCAPE", "Esc", 1.0)]);
+    row(&mut keys, 2.0, 0.0, &[("F1", "F1", 1.0), ("F2", "F2", 1.0), ("F3", "F3", 1.0), ("F4", "F4", 1.0)]);
+    row(&mut keys, 6.5, 0.0, &[("F5", "F5", 1.0), ("F6", "F6", 1.0), ("F7", "F7", 1.0), ("F8", "F8", 1.0)]);
+    row(&mut keys, 11.0, 0.0, &[("F9", "F9", 1.0), ("F10", "F10", 1.0), ("F11", "F11", 1.0), ("F12", "F12", 1.0)]);
+
+    let mut numbers = vec![("`", "`", 1.0)];
+    numbers.extend(letters("1234567890"));
+    numbers.extend([("-", "-", 1.0), ("=", "=", 1.0), ("BACKSPACE", "⌫", 2.0)]);
+    row(&mut keys, 0.0, 1.5, &numbers);
+    let mut top = vec![("TAB", "Tab", 1.5)];
+    top.extend(letters("QWERTYUIOP"));
+    top.extend([("[", "[", 1.0), ("]", "]", 1.0), ("\\", "\\", 1.5)]);
+    row(&mut keys, 0.0, 2.5, &top);
+    let mut home = vec![("CAPSLOCK", "Caps", 1.75)];
+    home.extend(letters("ASDFGHJKL"));
+    home.extend([("SEMICOLON", ";", 1.0), ("'", "'", 1.0), ("ENTER", "Enter", 2.25)]);
+    row(&mut keys, 0.0, 3.5, &home);
+    let mut bottom = vec![("SHIFT", "Shift", 2.25)];
+    bottom.extend(letters("ZXCVBNM"));
+    bottom.extend([(",", ",", 1.0), (".", ".", 1.0), ("/", "/", 1.0), ("RSHIFT", "Shift", 2.75)]);
+    row(&mut keys, 0.0, 4.5, &bottom);
+    row(&mut keys, 0.0, 5.5, &[
+        ("CTRL", "Ctrl", 1.25), ("LWIN", "Win", 1.25), ("ALT", "Alt", 1.25), ("SPACE", "Space", 6.25),
+        ("RALT", "Alt", 1.25), ("RWIN", "Win", 1.25), ("APP", "Menu", 1.25), ("RCTRL", "Ctrl", 1.25),
+    ]);
+
+    row(&mut keys, 15.5, 1.5, &[("INS", "Ins", 1.0), ("HOME", "Home", 1.0), ("PGUP", "PgUp", 1.0)]);
+    row(&mut keys, 15.5, 2.5, &[("DEL", "Del", 1.0), ("END", "End", 1.0), ("PGDN", "PgDn", 1.0)]);
+    row(&mut keys, 16.5, 4.5, &[("UPARROW", "↑", 1.0)]);
+    row(&mut keys, 15.5, 5.5, &[("LEFTARROW", "←", 1.0), ("DOWNARROW", "↓", 1.0), ("RIGHTARROW", "→", 1.0)]);
+
+    let mouse = [
+        ("MOUSE1", "M1", 19.5, 1.5, 1.25, 2.0),
+        ("MWHEELUP", "▲", 20.75, 1.5, 0.75, 0.67),
+        ("MOUSE3", "M3", 20.75, 2.17, 0.75, 0.66),
+        ("MWHEELDOWN", "▼", 20.75, 2.83, 0.75, 0.67),
+        ("MOUSE2", "M2", 21.5, 1.5, 1.25, 2.0),
+        ("MOUSE5", "M5", 19.5, 4.0, 1.25, 1.0),
+        ("MOUSE4", "M4", 19.5, 5.0, 1.25, 1.0),
+    ];
+    keys.extend(mouse.into_iter().map(|(name, label, x, y, w, h)| Key { name, label, x, y, w, h }));
+    keys
+}
+
+/// Whether `key` has its own cap in the drawn layout.
+pub fn is_drawn(key: &str) -> bool {
+    layout().iter().any(|k| k.name.eq_ignore_ascii_case(key))
+}
+
+/// Keys whose command differs between two binding sets, including keys bound in only one.
+pub fn differing_keys(a: &BindSet, b: &BindSet) -> Vec<String> {
+    let mut keys: Vec<String> = a.bindings.iter().chain(&b.bindings).map(|binding| binding.key.to_ascii_uppercase()).collect();
+    keys.sort();
+    keys.dedup();
+    keys.retain(|key| a.command(key) != b.command(key));
+    keys
+}
+
+/// Draws the keyboard and mouse with bound keys filled in. With `compare`, keys whose
+/// command differs from the other set are drawn in orange instead. Returns the name of
+/// a clicked key.
+pub fn keyboard_ui(ui: &mut egui::Ui, set: &BindSet, compare: Option<&BindSet>, selected: Option<&str>) -> Option<&'static str> {
+    let unit = (ui.available_width() / LAYOUT_WIDTH).clamp(18.0, 40.0);
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(LAYOUT_WIDTH, LAYOUT_HEIGHT) * unit, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals().clone();
+    let font = egui::FontId::proportional((unit * 0.32).max(9.0));
+    let mut clicked = None;
+    for key in layout() {
+        let min = rect.min + egui::vec2(key.x + KEY_GAP, key.y + KEY_GAP) * unit;
+        let cap = egui::Rect::from_min_size(min, egui::vec2(key.w - 2.0 * KEY_GAP, key.h - 2.0 * KEY_GAP) * unit);
+        let command = set.command(key.name);
+        let other = compare.map(|other| other.command(key.name));
+        let fill = match (command, other) {
+            (_, Some(other)) if other != command => DIFFERS,
+            (Some(_), _) => BOUND,
+            (None, _) => visuals.extreme_bg_color,
+        };
+        let response = ui.interact(cap, ui.id().with(("key", key.name)), egui::Sense::click());
+        let is_selected = selected.is_some_and(|s| s.eq_ignore_ascii_case(key.name));
+        let stroke = if is_selected {
+            egui::Stroke::new(2.0, SELECTED)
+        } else if response.hovered() {
+            visuals.widgets.hovered.fg_stroke
+        } else {
+            visuals.widgets.noninteractive.bg_stroke
+        };
+        painter.rect(cap, unit * 0.12, fill, stroke);
+        let text = if fill == visuals.extreme_bg_color { visuals.weak_text_color() } else { egui::Color32::WHITE };
+        painter.text(cap.center(), egui::Align2::CENTER_CENTER, key.label, font.clone(), text);
+
+        let mut hover = format!("{}: {}", key.name, command.unwrap_or("unbound"));
+        if let Some(other) = other {
+            hover.push_str(&format!("\nOther account: {}", other.unwrap_or("unbound")));
+        }
+        if response.on_hover_text(hover).clicked() {
+            clicked = Some(key.name);
+        }
+    }
+    clicked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binds::Binding;
+    use crate::kv3;
+    use std::path::PathBuf;
+
+    fn set(bindings: &[(&str, &str)]) -> BindSet {
+        let bindings = bindings.iter().map(|(key, command)| Binding { key: key.to_string(), command: command.to_string() }).collect();
+        BindSet { path: PathBuf::new(), document: kv3::Document::new(kv3::Object::new()), bindings, conflicts: Vec::new() }
+    }
+
+    #[test]
+    fn layout_names_are_unique() {
+        let keys = layout();
+        let mut names: Vec<&str> = keys.iter().map(|k| k.name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), keys.len());
+        assert!(is_drawn("mouse4") && is_drawn("w") && !is_drawn("KP_END"));
+    }
+
+    #[test]
+    fn differing_keys_ignore_case_and_include_one_sided_binds() {
+        let a = set(&[("w", "+forward"), ("MOUSE4", "+voicerecord"), ("F", "+lookatweapon")]);
+        let b = set(&[("W", "+forward"), ("MOUSE4", "+use"), ("G", "drop")]);
+        assert_eq!(differing_keys(&a, &b), vec!["F", "G", "MOUSE4"]);
+    }
+}
@@ -0,0 +1,724 @@
+//! Valve KeyValues3 text format, as used by CS2's `.vcfg` files.
+
+use std::fmt::Write as _;
+use std::ops::Range;
+
+/// The header every `.vcfg` file written by the game starts with.
+pub const DEFAULT_HEADER: &str = "<!-- kv3 encoding:text:version{e21c7f3c-8a33-41c5-9977-a76d3a32aa0d} format:generic:version{7412167c-06e9-4698-aff2-e63eb59037e7} -->";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Double(f64),
+    String(String),
+    Binary(Vec<u8>),
+    Array(Vec<Value>),
+    Object(Object),
+    /// A value with a type flag, e.g. `resource:"materials/x.vmat"`.
+    Flagged(String, Box<Value>),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            Value::Flagged(_, value) => value.as_str(),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&Object> {
+        match self {
+            Value::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+
+    pub fn as_object_mut(&mut self) -> Option<&mut Object> {
+        match self {
+            Value::Object(object) => Some(object),
+            _ => None,
+        }
+    }
+
+    /// The value as the game's console would print it.
+    pub fn to_display_string(&self) -> String {
+        if let Some(s) = self.as_str() {
+            return s.to_string();
+        }
+        let mut out = String::new();
+        write_value(&mut out, self, 0);
+        out
+    }
+}
+
+/// Key/value pairs in file order. Keys are unique; setting an existing key keeps its position.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Object {
+    entries: Vec<(String, Value)>,
+}
+
+impl Object {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.entries.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Sets `key`, appending it if it does not exist yet.
+    pub fn set(&mut self, key: &str, value: Value) {
+        match self.get_mut(key) {
+            Some(existing) => *existing = value,
+            None => self.entries.push((key.to_string(), value)),
+        }
+    }
+
+    /// The child object at `key`, created empty if missing or not an object.
+    pub fn object_mut(&mut self, key: &str) -> &mut Object {
+        if !matches!(self.get(key), Some(Value::Object(_))) {
+            self.set(key, Value::Object(Object::new()));
+        }
+        match self.get_mut(key) {
+            Some(Value::Object(object)) => object,
+            _ => unreachable!("object was just inserted"),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut Value)> {
+        self.entries.iter_mut().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+/// Where a parsed value sits in the source text.
+#[derive(Debug, Clone)]
+enum Span {
+    Value(Range<usize>),
+    Object { range: Range<usize>, entries: Vec<EntrySpan> },
+}
+
+impl Span {
+    fn range(&self) -> Range<usize> {
+        match self {
+            Span::Value(range) | Span::Object { range, .. } => range.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EntrySpan {
+    key: String,
+    /// Start of the key.
+    start: usize,
+    value: Span,
+}
+
+/// The text a document was parsed from, with the values as they were read, so that writing
+/// only touches what changed since.
+#[derive(Debug, Clone)]
+struct Source {
+    bom: bool,
+    text: String,
+    header: Range<usize>,
+    root: Value,
+    span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub header: String,
+    pub root: Value,
+    source: Option<Source>,
+}
+
+/// Documents are equal if their values are, however they were formatted.
+impl PartialEq for Document {
+    fn eq(&self, other: &Self) -> bool {
+        self.header == other.header && self.root == other.root
+    }
+}
+
+impl Document {
+    pub fn new(root: Object) -> Self {
+        Self { header: DEFAULT_HEADER.to_string(), root: Value::Object(root), source: None }
+    }
+
+    pub fn root_object(&self) -> Option<&Object> {
+        self.root.as_object()
+    }
+
+    pub fn root_object_mut(&mut self) -> Option<&mut Object> {
+        self.root.as_object_mut()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError { line: self.text[..self.pos].matches('\n').count() + 1, message: message.into() }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), ParseError> {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                let end = trimmed.find("*/").ok_or_else(|| self.error("unterminated comment"))?;
+                self.pos += end + 2;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        self.skip_whitespace()?;
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{}'", c)))
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        if self.rest().starts_with("\"\"\"") {
+            self.pos += 3;
+            let end = self.rest().find("\"\"\"").ok_or_else(|| self.error("unterminated multi-line string"))?;
+            let raw = &self.rest()[..end];
+            self.pos += end + 3;
+            // The content starts after the opening line break and ends before the closing one
+            let raw = raw.strip_prefix("\r\n").or_else(|| raw.strip_prefix('\n')).unwrap_or(raw);
+            let raw = raw.strip_suffix("\r\n").or_else(|| raw.strip_suffix('\n')).unwrap_or(raw);
+            return Ok(raw.to_string());
+        }
+        self.pos += 1;
+        let mut out = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(out);
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => out.push('\n'),
+                    Some((_, 't')) => out.push('\t'),
+                    Some((_, other)) => out.push(other),
+                    None => break,
+                },
+                c => out.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn parse_identifier(&mut self) -> String {
+        let len = self.rest().find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '-' | '+'))).unwrap_or(self.rest().len());
+        let word = &self.rest()[..len];
+        self.pos += len;
+        word.to_string()
+    }
+
+    fn parse_key(&mut self) -> Result<String, ParseError> {
+        self.skip_whitespace()?;
+        match self.peek() {
+            Some('"') => self.parse_string(),
+            Some(_) => {
+                let key = self.parse_identifier();
+                if key.is_empty() { Err(self.error("expected key")) } else { Ok(key) }
+            }
+            None => Err(self.error("unexpected end of file")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<(Object, Vec<EntrySpan>), ParseError> {
+        self.expect('{')?;
+        let mut object = Object::new();
+        let mut entries: Vec<EntrySpan> = Vec::new();
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                return Ok((object, entries));
+            }
+            let start = self.pos;
+            let key = self.parse_key()?;
+            self.expect('=')?;
+            let (value, span) = self.parse_value()?;
+            object.set(&key, value);
+            // A repeated key overrides the earlier one, so that is the occurrence to edit
+            entries.retain(|entry| entry.key != key);
+            entries.push(EntrySpan { key, start, value: span });
+            self.skip_whitespace()?;
+            if self.peek() == Some(',') {
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Vec<Value>, ParseError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(items);
+            }
+            items.push(self.parse_value()?.0);
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {}
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_binary(&mut self) -> Result<Vec<u8>, ParseError> {
+        self.pos += 1;
+        self.expect('[')?;
+        let end = self.rest().find(']').ok_or_else(|| self.error("unterminated binary blob"))?;
+        let hex: String = self.rest()[..end].chars().filter(|c| !c.is_whitespace()).collect();
+        self.pos += end + 1;
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()).ok_or_else(|| self.error("invalid binary blob")))
+            .collect()
+    }
+
+    fn parse_value(&mut self) -> Result<(Value, Span), ParseError> {
+        self.skip_whitespace()?;
+        let start = self.pos;
+        if self.peek() == Some('{') {
+            let (object, entries) = self.parse_object()?;
+            return Ok((Value::Object(object), Span::Object { range: start..self.pos, entries }));
+        }
+        let value = match self.peek() {
+            Some('[') => self.parse_array().map(Value::Array),
+            Some('"') => self.parse_string().map(Value::String),
+            Some('#') => self.parse_binary().map(Value::Binary),
+            Some(_) => {
+                let word = self.parse_identifier();
+                if self.peek() == Some(':') {
+                    self.pos += 1;
+                    let (value, _) = self.parse_value()?;
+                    Ok(Value::Flagged(word, Box::new(value)))
+                } else {
+                    match word.as_str() {
+                        "" => Err(self.error("expected value")),
+                        "null" => Ok(Value::Null),
+                        "true" => Ok(Value::Bool(true)),
+                        "false" => Ok(Value::Bool(false)),
+                        _ => word.parse::<i64>().map(Value::Int)
+                            .or_else(|_| word.parse::<f64>().map(Value::Double))
+                            .map_err(|_| self.error(format!("unexpected '{}'", word))),
+                    }
+                }
+            }
+            None => Err(self.error("unexpected end of file")),
+        }?;
+        Ok((value, Span::Value(start..self.pos)))
+    }
+}
+
+pub fn parse(text: &str) -> Result<Document, ParseError> {
+    let stripped = text.strip_prefix('\u{feff}');
+    let text = stripped.unwrap_or(text);
+    let mut parser = Parser { text, pos: 0 };
+    parser.skip_whitespace()?;
+    let header_start = parser.pos;
+    if parser.rest().starts_with("<!--") {
+        let end = parser.rest().find("-->").ok_or_else(|| parser.error("unterminated header"))? + 3;
+        parser.pos += end;
+    }
+    let header = header_start..parser.pos;
+    let (root, span) = parser.parse_value()?;
+    parser.skip_whitespace()?;
+    if !parser.rest().is_empty() {
+        return Err(parser.error("unexpected content after the root value"));
+    }
+    Ok(Document {
+        header: text[header.clone()].to_string(),
+        root: root.clone(),
+        source: Some(Source { bom: stripped.is_some(), text: text.to_string(), header, root, span }),
+    })
+}
+
+fn write_string(out: &mut String, s: &str) {
+    // Multi-line literals have no escapes, text containing their delimiter is escaped instead
+    if s.contains('\n') && !s.contains("\"\"\"") {
+        let _ = write!(out, "\"\"\"\n{}\n\"\"\"", s);
+        return;
+    }
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Shortest form that reads back as the same double, with a decimal point so it is not
+/// parsed as an integer. KV3 has no NaN or infinity: NaN is written as 0 and infinities as
+/// the largest finite value of their sign.
+fn write_double(out: &mut String, d: f64) {
+    let d = if d.is_nan() { 0.0 } else { d.clamp(f64::MIN, f64::MAX) };
+    let text = d.to_string();
+    out.push_str(&text);
+    if !text.contains('.') {
+        out.push_str(".0");
+    }
+}
+
+fn indent(out: &mut String, depth: usize) {
+    out.extend(std::iter::repeat_n('\t', depth));
+}
+
+fn write_value(out: &mut String, value: &Value, depth: usize) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Int(i) => { let _ = write!(out, "{}", i); }
+        Value::Double(d) => write_double(out, *d),
+        Value::String(s) => write_string(out, s),
+        Value::Binary(bytes) => {
+            out.push_str("#[");
+            for (i, b) in bytes.iter().enumerate() {
+                if i > 0 { out.push(' '); }
+                let _ = write!(out, "{:02X}", b);
+            }
+            out.push(']');
+        }
+        Value::Array(items) => {
+            out.push_str("[\n");
+            for item in items {
+                indent(out, depth + 1);
+                write_value(out, item, depth + 1);
+                out.push_str(",\n");
+            }
+            indent(out, depth);
+            out.push(']');
+        }
+        Value::Object(object) => {
+            out.push_str("{\n");
+            for (key, value) in object.iter() {
+                indent(out, depth + 1);
+                write_entry(out, key, value, depth);
+                out.push('\n');
+            }
+            indent(out, depth);
+            out.push('}');
+        }
+        Value::Flagged(flag, value) => {
+            let _ = write!(out, "{}:", flag);
+            write_value(out, value, depth);
+        }
+    }
+}
+
+/// `"key" = value` for an entry of an object at `depth`. Containers open on the next line,
+/// so there is no trailing space after '='.
+fn write_entry(out: &mut String, key: &str, value: &Value, depth: usize) {
+    write_string(out, key);
+    if matches!(value, Value::Object(_) | Value::Array(_)) {
+        out.push_str(" =\n");
+        indent(out, depth + 1);
+    } else {
+        out.push_str(" = ");
+    }
+    write_value(out, value, depth + 1);
+}
+
+impl Source {
+    fn line_start(&self, pos: usize) -> usize {
+        self.text[..pos].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn newline(&self) -> &'static str {
+        if self.text.contains("\r\n") { "\r\n" } else { "\n" }
+    }
+
+    /// The source text with only the values that differ from the parsed ones rewritten.
+    fn write(&self, document: &Document) -> String {
+        let mut edits = Vec::new();
+        if document.header != self.text[self.header.clone()] {
+            let header = if self.header.is_empty() { format!("{}{}", document.header, self.newline()) } else { document.header.clone() };
+            edits.push((self.header.clone(), header));
+        }
+        self.diff(&self.root, &document.root, &self.span, 0, &mut edits);
+        edits.sort_by_key(|(range, _)| range.start);
+
+        let mut out = String::from(if self.bom { "\u{feff}" } else { "" });
+        let mut pos = 0;
+        for (range, replacement) in edits {
+            out.push_str(&self.text[pos..range.start]);
+            out.push_str(&replacement);
+            pos = range.end;
+        }
+        out.push_str(&self.text[pos..]);
+        out
+    }
+
+    fn diff(&self, old: &Value, new: &Value, span: &Span, depth: usize, edits: &mut Vec<(Range<usize>, String)>) {
+        if old == new {
+            return;
+        }
+        if let (Value::Object(old), Value::Object(new), Span::Object { range, entries }) = (old, new, span) {
+            for entry in entries {
+                match (old.get(&entry.key), new.get(&entry.key)) {
+                    (Some(old_value), Some(new_value)) => self.diff(old_value, new_value, &entry.value, depth + 1, edits),
+                    _ => edits.push((self.entry_range(entry), String::new())),
+                }
+            }
+            let added: Vec<(&str, &Value)> = new.iter().filter(|(key, _)| old.get(key).is_none()).collect();
+            if !added.is_empty() {
+                edits.push(self.insertion(range.end - 1, entries.last(), &added, depth));
+            }
+            return;
+        }
+        let mut out = String::new();
+        write_value(&mut out, new, depth);
+        edits.push((span.range(), out));
+    }
+
+    /// The text of a removed entry: its whole line if nothing else is on it, otherwise just
+    /// the entry and its separator.
+    fn entry_range(&self, entry: &EntrySpan) -> Range<usize> {
+        let line_start = self.line_start(entry.start);
+        let after = self.text[entry.value.range().end..].trim_start_matches([' ', '\t']);
+        let after = after.strip_prefix(',').unwrap_or(after).trim_start_matches([' ', '\t']);
+        if self.text[line_start..entry.start].trim().is_empty() {
+            if let Some(next_line) = after.strip_prefix("\r\n").or_else(|| after.strip_prefix('\n')) {
+                return line_start..self.text.len() - next_line.len();
+            }
+        }
+        entry.start..self.text.len() - after.len()
+    }
+
+    /// Added entries go on their own lines before the closing brace at `close`, indented
+    /// like the last existing entry, or inline if the brace shares its line.
+    fn insertion(&self, close: usize, last: Option<&EntrySpan>, added: &[(&str, &Value)], depth: usize) -> (Range<usize>, String) {
+        let mut out = String::new();
+        let close_line = self.line_start(close);
+        if !self.text[close_line..close].trim().is_empty() {
+            for (key, value) in added {
+                write_entry(&mut out, key, value, depth);
+                out.push(' ');
+            }
+            return (close..close, out);
+        }
+        let indentation = last
+            .map(|entry| &self.text[self.line_start(entry.start)..entry.start])
+            .filter(|indentation| indentation.trim().is_empty())
+            .map_or_else(|| "\t".repeat(depth + 1), str::to_string);
+        for (key, value) in added {
+            out.push_str(&indentation);
+            write_entry(&mut out, key, value, depth);
+            out.push_str(self.newline());
+        }
+        (close_line..close_line, out)
+    }
+}
+
+/// Writes the document back into the text it was parsed from, rewriting only values that
+/// changed: comments, whitespace and key order are kept, and added keys go at the end of
+/// their object. Documents that were not parsed are written in the layout the game itself
+/// uses: tab indentation, quoted keys, and nested objects opening on their own line.
+pub fn write(document: &Document) -> String {
+    if let Some(source) = &document.source {
+        return source.write(document);
+    }
+    let mut out = String::new();
+    if !document.header.is_empty() {
+        out.push_str(&document.header);
+        out.push('\n');
+    }
+    write_value(&mut out, &document.root, 0);
+    out.push('\n');
+    out
+}
+
+/// Every leaf as `path/to/key = value`, in file order. Useful for diffs that should not
+/// depend on formatting.
+pub fn flatten(value: &Value) -> Vec<String> {
+    fn walk(value: &Value, path: &str, out: &mut Vec<String>) {
+        match value {
+            Value::Object(object) => {
+                for (key, child) in object.iter() {
+                    let child_path = if path.is_empty() { key.to_string() } else { format!("{}/{}", path, key) };
+                    walk(child, &child_path, out);
+                }
+            }
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    walk(item, &format!("{}[{}]", path, i), out);
+                }
+            }
+            leaf => out.push(format!("{} = {}", path, leaf.to_display_string())),
+        }
+    }
+    let mut out = Vec::new();
+    walk(value, "", &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER_CONVARS: &str = "<!-- kv3 encoding:text:version{e21c7f3c-8a33-41c5-9977-a76d3a32aa0d} format:generic:version{7412167c-06e9-4698-aff2-e63eb59037e7} -->
+{
+\t\"version\" = 1
+\t\"convars\" =
+\t{
+\t\t\"cl_crosshairgap\" = \"-3.000000\"
+\t\t\"cl_crosshairsize\" = \"2.000000\"
+\t\t\"cl_crosshair_drawoutline\" = \"false\"
+\t}
+}
+";
+
+    #[test]
+    fn parses_user_convars() {
+        let document = parse(USER_CONVARS).unwrap();
+        assert!(document.header.starts_with("<!-- kv3"));
+        let convars = document.root_object().and_then(|root| root.get("convars")).and_then(Value::as_object).unwrap();
+        assert_eq!(convars.iter().count(), 3);
+        assert_eq!(convars.get("cl_crosshairgap").and_then(Value::as_str), Some("-3.000000"));
+        assert_eq!(document.root_object().unwrap().get("version"), Some(&Value::Int(1)));
+    }
+
+    #[test]
+    fn writes_new_documents_in_game_layout() {
+        let parsed = parse(USER_CONVARS).unwrap();
+        let document = Document::new(parsed.root_object().unwrap().clone());
+        let written = write(&document);
+        assert_eq!(written, USER_CONVARS);
+        assert_eq!(parse(&written).unwrap(), document);
+    }
+
+    #[test]
+    fn parses_arrays_flags_comments_and_multiline_strings() {
+        let text = "{\n\t// comment\n\tlist = [ 1, 2.5, \"three\", [ true, null ], ]\n\tres = resource:\"a/b.vmat\"\n\t/* block */ blob = #[ 01 FF ]\n\ttext = \"\"\"\nline one\nline two\n\"\"\"\n\tescaped = \"say \\\"hi\\\"\"\n}";
+        let document = parse(text).unwrap();
+        let root = document.root_object().unwrap();
+        assert_eq!(root.get("list"), Some(&Value::Array(vec![
+            Value::Int(1), Value::Double(2.5), Value::String("three".into()), Value::Array(vec![Value::Bool(true), Value::Null]),
+        ])));
+        assert_eq!(root.get("res"), Some(&Value::Flagged("resource".into(), Box::new(Value::String("a/b.vmat".into())))));
+        assert_eq!(root.get("blob"), Some(&Value::Binary(vec![1, 255])));
+        assert_eq!(root.get("text").and_then(Value::as_str), Some("line one\nline two"));
+        assert_eq!(root.get("escaped").and_then(Value::as_str), Some("say \"hi\""));
+        assert_eq!(parse(&write(&document)).unwrap(), document);
+    }
+
+    #[test]
+    fn round_trips_doubles_exactly() {
+        let mut root = Object::new();
+        for (i, d) in [0.1, 1.0, -2.5, 0.022, 1e-7, 123456.789012345].into_iter().enumerate() {
+            root.set(&format!("d{}", i), Value::Double(d));
+        }
+        let document = Document::new(root);
+        let written = write(&document);
+        assert!(written.contains("\"d1\" = 1.0\n") && written.contains("\"d2\" = -2.5\n"), "{}", written);
+        assert_eq!(parse(&written).unwrap(), document);
+    }
+
+    #[test]
+    fn escapes_strings_containing_triple_quotes() {
+        let mut root = Object::new();
+        root.set("quoted", Value::String("a \"\"\" b\nsecond line".into()));
+        root.set("multi", Value::String("\nline one\nline two\n".into()));
+        let document = Document::new(root);
+        let written = write(&document);
+        assert!(written.contains(r#""quoted" = "a \"\"\" b\nsecond line""#), "{}", written);
+        assert_eq!(parse(&written).unwrap(), document);
+    }
+
+    const COMMENTED: &str = "\u{feff}<!-- kv3 -->\n// written by hand\n{\n    a = \"1\" /* inline */\n    \"b\" =   2, // spaced\n    nested = { x = 1.5 }\n    \"list\" = [ 1, 2 ]\n}\n";
+
+    #[test]
+    fn writes_unchanged_files_back_byte_for_byte() {
+        for text in [COMMENTED, USER_CONVARS] {
+            assert_eq!(write(&parse(text).unwrap()), text);
+        }
+    }
+
+    #[test]
+    fn rewrites_only_the_values_that_changed() {
+        let mut document = parse(COMMENTED).unwrap();
+        let root = document.root_object_mut().unwrap();
+        root.set("a", Value::String("one".into()));
+        root.set("list", Value::Array(vec![Value::Int(3)]));
+        root.object_mut("nested").set("y", Value::Bool(true));
+        root.entries.retain(|(key, _)| key != "b");
+        root.set("added", Value::Int(7));
+        let written = write(&document);
+        assert_eq!(written, "\u{feff}<!-- kv3 -->\n// written by hand\n{\n    a = \"one\" /* inline */\n    // spaced\n    nested = { x = 1.5 \"y\" = true }\n    \"list\" = [\n\t\t3,\n\t]\n    \"added\" = 7\n}\n");
+        assert_eq!(parse(&written).unwrap(), document);
+    }
+
+    #[test]
+    fn writes_non_finite_doubles_as_values_that_parse() {
+        let mut root = Object::new();
+        for (key, d) in [("nan", f64::NAN), ("inf", f64::INFINITY), ("-inf", f64::NEG_INFINITY)] {
+            root.set(key, Value::Double(d));
+        }
+        let read = parse(&write(&Document::new(root))).unwrap();
+        let read = read.root_object().unwrap();
+        assert_eq!(read.get("nan"), Some(&Value::Double(0.0)));
+        assert_eq!(read.get("inf"), Some(&Value::Double(f64::MAX)));
+        assert_eq!(read.get("-inf"), Some(&Value::Double(f64::MIN)));
+    }
+
+    #[test]
+    fn reports_error_line() {
+        let error = parse("{\n\t\"a\" = \"1\"\n\t\"b\" =\n}").unwrap_err();
+        assert_eq!(error.line, 4);
+    }
+}
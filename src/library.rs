@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::CrosshairProfile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LibrarySort {
+    Name,
+    Color,
+    Style,
+    DateAdded,
+}
+
+impl LibrarySort {
+    pub const ALL: [LibrarySort; 4] = [LibrarySort::Name, LibrarySort::Color, LibrarySort::Style, LibrarySort::DateAdded];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LibrarySort::Name => "Name",
+            LibrarySort::Color => "Color",
+            LibrarySort::Style => "Style",
+            LibrarySort::DateAdded => "Date added",
+        }
+    }
+}
+
+/// One level of the library folder tree. Folder paths are `/`-separated, e.g. `Pros/NaVi`.
+#[derive(Debug, Default)]
+pub struct FolderNode {
+    pub children: BTreeMap<String, FolderNode>,
+    pub entries: Vec<usize>,
+}
+
+impl FolderNode {
+    pub fn count(&self) -> usize {
+        self.entries.len() + self.children.values().map(FolderNode::count).sum::<usize>()
+    }
+}
+
+pub fn now_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Splits a comma separated tag string into trimmed, deduplicated tags.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+/// Normalizes a folder path: trims every segment and drops empty ones.
+pub fn normalize_folder(input: &str) -> String {
+    input.split(['/', '\\']).map(str::trim).filter(|s| !s.is_empty()).collect::<Vec<_>>().join("/")
+}
+
+pub fn matches_search(profile: &CrosshairProfile, search: &str) -> bool {
+    if search.is_empty() { return true; }
+    let search = search.to_lowercase();
+    profile.name.to_lowercase().contains(&search)
+        || profile.folder.to_lowercase().contains(&search)
+        || profile.tags.iter().any(|t| t.to_lowercase().contains(&search))
+}
+
+/// Hue of the profile color in degrees, used to order the library by color.
+fn hue(profile: &CrosshairProfile) -> f32 {
+    let (r, g, b) = (profile.red as f32 / 255.0, profile.green as f32 / 255.0, profile.blue as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    if delta == 0.0 { return 0.0; }
+    let h = if max == r {
+        60.0 * (((g - b) / delta) % 6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    if h < 0.0 { h + 360.0 } else { h }
+}
+
+/// Returns the indices of all library entries passing the filters, in display order.
+/// Favorites are always listed first.
+pub fn filtered_sorted(library: &[CrosshairProfile], search: &str, favorites_only: bool, sort: LibrarySort) -> Vec<usize> {
+    let mut indices: Vec<usize> = library.iter().enumerate()
+        .filter(|(_, p)| (!favorites_only || p.favorite) && matches_search(p, search))
+        .map(|(idx, _)| idx)
+        .collect();
+    indices.sort_by(|&a, &b| {
+        let (pa, pb) = (&library[a], &library[b]);
+        pb.favorite.cmp(&pa.favorite).then_with(|| match sort {
+            LibrarySort::Name => pa.name.to_lowercase().cmp(&pb.name.to_lowercase()),
+            LibrarySort::Color => pa.color.cmp(&pb.color).then_with(|| hue(pa).total_cmp(&hue(pb))),
+            LibrarySort::Style => pa.style.cmp(&pb.style),
+            LibrarySort::DateAdded => pb.added_at.cmp(&pa.added_at),
+        }).then_with(|| a.cmp(&b))
+    });
+    indices
+}
+
+/// Groups already filtered and sorted indices into a folder tree, keeping their order.
+pub fn build_tree(library: &[CrosshairProfile], indices: &[usize]) -> FolderNode {
+    let mut root = FolderNode::default();
+    for &idx in indices {
+        let mut node = &mut root;
+        for segment in library[idx].folder.split('/').filter(|s| !s.is_empty()) {
+            node = node.children.entry(segment.to_string()).or_default();
+        }
+        node.entries.push(idx);
+    }
+    root
+}
+
+pub fn all_folders(library: &[CrosshairProfile]) -> Vec<String> {
+    let mut folders: Vec<String> = library.iter().map(|p| p.folder.clone()).filter(|f| !f.is_empty()).collect();
+    folders.sort();
+    folders.dedup();
+    folders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::share_code;
+
+    fn profile(name: &str, folder: &str, tags: &[&str], favorite: bool, added_at: u64) -> CrosshairProfile {
+        let mut profile = CrosshairProfile { name: name.to_string(), ..share_code::sample_profile() };
+        profile.folder = folder.to_string();
+        profile.tags = tags.iter().map(|t| t.to_string()).collect();
+        profile.favorite = favorite;
+        profile.added_at = added_at;
+        profile
+    }
+
+    #[test]
+    fn parse_tags_trims_and_dedupes_ignoring_case() {
+        assert_eq!(parse_tags(" awp, Entry ,,awp, AWP ,entry"), vec!["awp", "Entry"]);
+        assert!(parse_tags(" , ").is_empty());
+    }
+
+    #[test]
+    fn normalize_folder_accepts_both_separators() {
+        assert_eq!(normalize_folder(" Pros / NaVi\\ s1mple /"), "Pros/NaVi/s1mple");
+        assert_eq!(normalize_folder("//"), "");
+    }
+
+    #[test]
+    fn filtered_sorted_lists_favorites_first_and_filters() {
+        let library = vec![
+            profile("zywoo", "Pros/Vitality", &["awp"], false, 3),
+            profile("Device", "Pros/Astralis", &[], true, 1),
+            profile("apEX", "Pros/Vitality", &["entry"], false, 2),
+        ];
+        assert_eq!(filtered_sorted(&library, "", false, LibrarySort::Name), vec![1, 2, 0]);
+        assert_eq!(filtered_sorted(&library, "", false, LibrarySort::DateAdded), vec![1, 0, 2]);
+        assert_eq!(filtered_sorted(&library, "vitality", false, LibrarySort::Name), vec![2, 0]);
+        assert_eq!(filtered_sorted(&library, "AWP", false, LibrarySort::Name), vec![0]);
+        assert_eq!(filtered_sorted(&library, "", true, LibrarySort::Name), vec![1]);
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use serde::Serialize;
+
+use crate::convars::{self, ConvarCategory};
+use crate::kv3::Value;
+use crate::vcfg::{self, VcfgKind};
+use crate::{apply, binds};
+
+/// Console commands that are not convars, so they are never reported as unknown.
+const COMMANDS: [&str; 22] = [
+    "alias", "bind", "unbind", "unbindall", "exec", "echo", "say", "say_team", "toggle", "incrementvar",
+    "host_writeconfig", "clear", "connect", "disconnect", "buy", "drop", "use", "jointeam", "quit", "map",
+    "lastinv", "invnext",
+];
+
+/// Commands from CS:GO binds that do nothing in CS2.
+const REMOVED_COMMANDS: [&str; 5] = ["autobuy", "rebuy", "+klook", "+mlook", "+jlook"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    pub file: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub account: String,
+    pub convar_database_version: u32,
+    pub findings: Vec<Finding>,
+}
+
+impl Report {
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings.iter().filter(|f| f.severity == severity).count()
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+}
+
+struct Linter {
+    findings: Vec<Finding>,
+    /// Folders `exec` looks in: the account's cfg folder, then the game's.
+    exec_dirs: Vec<PathBuf>,
+    aliases: BTreeSet<String>,
+    /// `(file, line)` of the first crosshair convar set by each file.
+    crosshair_sources: Vec<(String, Option<usize>)>,
+}
+
+impl Linter {
+    fn push(&mut self, severity: Severity, file: &str, line: Option<usize>, message: String) {
+        self.findings.push(Finding { severity, file: file.to_string(), line, message });
+    }
+
+    /// Checks one `name value` assignment, shared by cfg scripts and vcfg files.
+    fn check_convar(&mut self, file: &str, line: Option<usize>, name: &str, value: Option<&str>) {
+        match convars::lookup(name) {
+            Some(info) if info.removed.is_some() => {
+                self.push(Severity::Warning, file, line, format!("{} is a CS:GO convar that CS2 ignores ({})", name, info.removed.unwrap_or_default()));
+            }
+            Some(info) => {
+                if let Some(problem) = value.and_then(|v| info.check(v)) {
+                    self.push(Severity::Warning, file, line, format!("{}: {}", name, problem));
+                }
+                if info.category == ConvarCategory::Crosshair && !self.crosshair_sources.iter().any(|(f, _)| f == file) {
+                    self.crosshair_sources.push((file.to_string(), line));
+                }
+            }
+            None => self.push(Severity::Info, file, line, format!("{} is not in the convar database, it may be misspelled or removed", name)),
+        }
+    }
+
+    fn check_bind(&mut self, file: &str, line: Option<usize>, key: &str, command: &str) {
+        for action in command.split(';').filter_map(|a| a.split_whitespace().next()) {
+            let removed = REMOVED_COMMANDS.iter().any(|c| c.eq_ignore_ascii_case(action))
+                || convars::lookup(action).is_some_and(|info| info.removed.is_some());
+            if removed {
+                self.push(Severity::Warning, file, line, format!("{} is bound to {}, which was removed in CS2", key, action));
+            }
+        }
+    }
+
+    fn check_cfg(&mut self, name: &str, text: &str) {
+        let mut assigned: HashMap<String, usize> = HashMap::new();
+        for (idx, line) in text.lines().enumerate() {
+            let number = idx + 1;
+            for statement in statements(line) {
+                let Some(command) = statement.first().map(|c| c.to_ascii_lowercase()) else { continue };
+                let args = &statement[1..];
+                let target = match command.as_str() {
+                    "exec" => {
+                        if let Some(file) = args.first() {
+                            let found = self.exec_dirs.iter().any(|dir| dir.join(file).exists() || dir.join(format!("{}.cfg", file)).exists());
+                            if !found {
+                                self.push(Severity::Warning, name, Some(number), format!("exec {}: file not found in the account's or the game's cfg folder", file));
+                            }
+                        }
+                        None
+                    }
+                    "bind" if args.len() >= 2 => {
+                        self.check_bind(name, Some(number), &args[0], &args[1]);
+                        Some(format!("bind {}", args[0].to_ascii_uppercase()))
+                    }
+                    _ if COMMANDS.contains(&command.as_str()) || command.starts_with('+') || command.starts_with('-') || command.starts_with("slot") => None,
+                    _ if self.aliases.contains(&command) => None,
+                    _ => {
+                        self.check_convar(name, Some(number), &command, args.first().map(String::as_str));
+                        (!args.is_empty()).then(|| command.clone())
+                    }
+                };
+                if let Some(target) = target {
+                    if let Some(previous) = assigned.insert(target.clone(), number) {
+                        self.push(Severity::Warning, name, Some(number), format!("{} overrides the assignment on line {}", target, previous));
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_vcfg(&mut self, name: &str, kind: VcfgKind, text: &str) {
+        let document = match crate::kv3::parse(text) {
+            Ok(document) => document,
+            Err(e) => {
+                self.push(Severity::Warning, name, Some(e.line), format!("Could not parse: {}", e.message));
+                return;
+            }
+        };
+        match kind {
+            VcfgKind::UserConvars => {
+                let Some(entries) = document.root_object().and_then(|root| root.get("convars")).and_then(Value::as_object) else { return };
+                // The game writes many convars the database does not cover, only known ones
+                // are checked here. Unknown names are reported for cfg scripts only.
+                for (key, value) in entries.iter().filter(|(key, _)| convars::lookup(key).is_some()) {
+                    self.check_convar(name, line_of(text, key), key, value.as_str());
+                }
+            }
+            VcfgKind::KeyBindings => {
+                for binding in binds::read_bindings(&document) {
+                    self.check_bind(name, line_of(text, &binding.key), &binding.key, &binding.command);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Line of the first `"key"` in a vcfg file.
+fn line_of(text: &str, key: &str) -> Option<usize> {
+    let quoted = format!("\"{}\"", key);
+    text.lines().position(|line| line.trim_start().starts_with(&quoted)).map(|idx| idx + 1)
+}
+
+/// Splits a cfg line into `;` separated statements of tokens. Quotes group tokens and
+/// protect `;`, `//` starts a comment.
+fn statements(line: &str) -> Vec<Vec<String>> {
+    let mut statements = vec![Vec::new()];
+    let mut token = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    let finish = |token: &mut String, statements: &mut Vec<Vec<String>>, force: bool| {
+        if force || !token.is_empty() {
+            statements.last_mut().expect("at least one statement").push(std::mem::take(token));
+        }
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                finish(&mut token, &mut statements, true);
+                quoted = false;
+            }
+            '"' => {
+                finish(&mut token, &mut statements, false);
+                quoted = true;
+            }
+            _ if quoted => token.push(c),
+            '/' if chars.peek() == Some(&'/') => break,
+            ';' => {
+                finish(&mut token, &mut statements, false);
+                statements.push(Vec::new());
+            }
+            c if c.is_whitespace() => finish(&mut token, &mut statements, false),
+            c => token.push(c),
+        }
+    }
+    finish(&mut token, &mut statements, quoted);
+    statements.retain(|s| !s.is_empty());
+    statements
+}
+
+/// The `game/csgo/cfg` folder of the CS2 install, searched in every Steam library listed
+/// in `steamapps/libraryfolders.vdf`.
+pub fn game_cfg_dir(steam_path: &Path) -> Option<PathBuf> {
+    let path_re = Regex::new(r#""path"\s+"([^"]+)""#).expect("valid library path regex");
+    let folders = fs::read_to_string(steam_path.join("steamapps").join("libraryfolders.vdf")).unwrap_or_default();
+    let libraries: Vec<PathBuf> = std::iter::once(steam_path.to_path_buf())
+        .chain(path_re.captures_iter(&folders).map(|c| PathBuf::from(c[1].replace("\\\\", "\\"))))
+        .collect();
+    libraries.into_iter()
+        .map(|library| library.join("steamapps/common/Counter-Strike Global Offensive/game/csgo/cfg"))
+        .find(|dir| dir.is_dir())
+}
+
+/// Checks every cfg script and settings file in an account's `cfg` folder. `exec` targets
+/// are also looked up in `game_cfg_dir`, see [`game_cfg_dir`].
+pub fn check_account(account: &str, cfg_dir: &Path, game_cfg_dir: Option<&Path>) -> Report {
+    let exec_dirs = std::iter::once(cfg_dir).chain(game_cfg_dir).map(Path::to_path_buf).collect();
+    let mut linter = Linter { findings: Vec::new(), exec_dirs, aliases: BTreeSet::new(), crosshair_sources: Vec::new() };
+    let mut scripts: Vec<(String, String)> = fs::read_dir(cfg_dir).into_iter().flatten().flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            name.to_ascii_lowercase().ends_with(".cfg").then(|| Some((name, fs::read_to_string(entry.path()).ok()?))).flatten()
+        })
+        .collect();
+    scripts.sort();
+    for (_, text) in &scripts {
+        for statement in text.lines().flat_map(statements) {
+            if statement.first().is_some_and(|c| c.eq_ignore_ascii_case("alias")) {
+                if let Some(alias) = statement.get(1) {
+                    linter.aliases.insert(alias.to_ascii_lowercase());
+                }
+            }
+        }
+    }
+    for (name, text) in &scripts {
+        linter.check_cfg(name, text);
+    }
+
+    let files = vcfg::discover(cfg_dir);
+    let main_files = [apply::user_convars_file(&files), binds::keys_file(&files)];
+    for file in main_files.into_iter().flatten() {
+        if let Ok(text) = fs::read_to_string(cfg_dir.join(&file.name)) {
+            linter.check_vcfg(&file.name, file.kind, &text);
+        }
+    }
+
+    if linter.crosshair_sources.len() > 1 {
+        let sources = linter.crosshair_sources.clone();
+        let names: Vec<&str> = sources.iter().map(|(file, _)| file.as_str()).collect();
+        for (file, line) in &sources {
+            let others: Vec<&str> = names.iter().copied().filter(|n| n != file).collect();
+            linter.push(Severity::Warning, file, *line, format!("Crosshair is also set in {}; whichever runs last wins", others.join(", ")));
+        }
+    }
+    let mut findings = linter.findings;
+    findings.sort_by(|a, b| (std::cmp::Reverse(a.severity), &a.file, a.line).cmp(&(std::cmp::Reverse(b.severity), &b.file, b.line)));
+    Report { account: account.to_string(), convar_database_version: convars::DATABASE_VERSION, findings }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_statements_with_quotes_and_comments() {
+        let parsed = statements(r#"bind "MOUSE4" "+jump; -attack"; cl_crosshairsize 3 // comment; ignored"#);
+        assert_eq!(parsed, vec![vec!["bind", "MOUSE4", "+jump; -attack"], vec!["cl_crosshairsize", "3"]]);
+        assert_eq!(statements(r#"echo """#), vec![vec!["echo", ""]]);
+    }
+
+    #[test]
+    fn reports_cfg_problems_with_line_numbers() {
+        let root = std::env::temp_dir().join(format!("cs2man_lint_{}", std::process::id()));
+        let (dir, game_dir) = (root.join("user"), root.join("game"));
+        fs::create_dir_all(&dir).unwrap();
+        fs::create_dir_all(&game_dir).unwrap();
+        fs::write(game_dir.join("gamemode_competitive.cfg"), "").unwrap();
+        let autoexec = "cl_crosshairsize 3\nnet_graph 1\ncl_crosshairsize 150\nexec missing_file\nbind F \"rebuy\"\nalias jt \"+jump\"\njt\nexec gamemode_competitive\n";
+        fs::write(dir.join("autoexec.cfg"), autoexec).unwrap();
+        fs::write(dir.join("cs2_user_convars_0_slot0.vcfg"), "{\n\t\"convars\" =\n\t{\n\t\t\"cl_crosshairgap\" = \"-2.000000\"\n\t\t\"some_new_convar\" = \"1\"\n\t}\n}\n").unwrap();
+        let report = check_account("test", &dir, Some(&game_dir));
+        fs::remove_dir_all(&root).unwrap();
+
+        let at = |file: &str, line: usize, text: &str| report.findings.iter().any(|f| f.file == file && f.line == Some(line) && f.message.contains(text));
+        assert!(at("autoexec.cfg", 2, "CS:GO convar"), "{:#?}", report.findings);
+        assert!(at("autoexec.cfg", 3, "maximum"));
+        assert!(at("autoexec.cfg", 3, "overrides the assignment on line 1"));
+        assert!(at("autoexec.cfg", 4, "not found"));
+        assert!(at("autoexec.cfg", 5, "rebuy"));
+        assert!(at("autoexec.cfg", 1, "Crosshair is also set in cs2_user_convars_0_slot0.vcfg"));
+        assert!(at("cs2_user_convars_0_slot0.vcfg", 4, "Crosshair is also set in autoexec.cfg"));
+        assert!(!report.findings.iter().any(|f| f.message.starts_with("jt ")));
+        assert!(!report.findings.iter().any(|f| f.line == Some(8) || f.message.contains("some_new_convar")), "{:#?}", report.findings);
+        assert!(report.to_json().unwrap().contains("\"severity\": \"warning\""));
+    }
+}
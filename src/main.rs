@@ -1,13 +1,54 @@
 #![windows_subsystem = "windows"]
 
+use std::collections::BTreeSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use eframe::egui;
 use serde::{Deserialize, Serialize};
-use num_bigint::BigUint;
-use num_traits::{Zero, One};
+
+mod analysis;
+mod apply;
+mod binds;
+mod bulk_import;
+mod cli;
+mod compare;
+mod convar_profiles;
+mod convars;
+mod editor;
+mod export;
+mod history;
+mod keyboard;
+mod kv3;
+mod library;
+mod lint;
+mod pack;
+mod preview;
+mod raster;
+mod render;
+mod sensitivity;
+mod settings;
+mod share_code;
+mod similarity;
+mod vcfg;
+mod viewmodel;
+mod vision;
+
+use binds::{BindSet, Binding};
+use bulk_import::FoundCode;
+use convar_profiles::{CategoryState, ConvarProfile, CATEGORIES};
+use export::{ExportBackground, ExportOptions};
+use history::{History, Snapshot};
+use library::{FolderNode, LibrarySort};
+use pack::{ConflictResolution, ImportStatus, PendingImport};
+use raster::RgbaBuffer;
+use render::{AspectMode, Resolution, SpreadState};
+use sensitivity::Sensitivity;
+use settings::{AppSettings, PreviewBackground};
+use vcfg::{DiffKind, DiffLine, VcfgFile};
+use viewmodel::ViewmodelProfile;
+use vision::{ColorVision, ContrastRating};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct SteamAccount {
@@ -16,6 +57,8 @@ struct SteamAccount {
     cs2_config_path: Option<PathBuf>,
     has_cs2_config: bool,
     config_files: Vec<String>,
+    #[serde(default)]
+    settings_files: Vec<VcfgFile>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +86,14 @@ struct CrosshairProfile {
     size: f32,
     name: String,
     original_code: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    folder: String,
+    #[serde(default)]
+    favorite: bool,
+    #[serde(default)]
+    added_at: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -79,6 +130,74 @@ pub struct CS2ConfigApp {
     selected_library_idx: Option<usize>,
     active_profile: CrosshairProfile,
     crosshair_code_input: String,
+    library_search: String,
+    library_sort: LibrarySort,
+    library_favorites_only: bool,
+    library_tags_input: String,
+    library_folder_input: String,
+    pack_selection: BTreeSet<usize>,
+    pack_path: String,
+    pack_author: String,
+    pack_description: String,
+    pending_import: Option<PendingImport>,
+    bulk_text: String,
+    bulk_path: String,
+    bulk_results: Vec<FoundCode>,
+    preview_resolution: Resolution,
+    preview_zoom: f32,
+    preview_texture: Option<egui::TextureHandle>,
+    preview_state: SpreadState,
+    preview_cycle: bool,
+    preview_spread: f32,
+    export_options: ExportOptions,
+    export_dir: String,
+    export_bg_path: String,
+    settings: AppSettings,
+    preview_bg_input: String,
+    preview_background: Option<(String, RgbaBuffer)>,
+    /// Saved background that could not be loaded, kept in the settings but not retried.
+    preview_background_failed: Option<String>,
+    compare_a: Option<usize>,
+    compare_b: Option<usize>,
+    compare_overlay: bool,
+    compare_opacity: f32,
+    compare_textures: [Option<egui::TextureHandle>; 2],
+    similar_code_input: String,
+    similar_results: Vec<(usize, f32)>,
+    history: History,
+    preview_vision: ColorVision,
+    preview_resolution_input: String,
+    preview_monitor: Resolution,
+    preview_aspect: AspectMode,
+    preview_grid: bool,
+    vcfg_diff: Option<(String, Vec<DiffLine>)>,
+    vcfg_editor: Option<(PathBuf, kv3::Document)>,
+    vcfg_editor_filter: String,
+    apply_convars_file: Option<String>,
+    apply_autoexec: bool,
+    binds: Option<BindSet>,
+    binds_filter: String,
+    binds_new_key: String,
+    binds_new_command: String,
+    binds_keyboard: bool,
+    binds_compare: Option<usize>,
+    binds_compare_set: Option<BindSet>,
+    /// Compare file that failed to open, not retried until the selection changes.
+    binds_compare_failed: Option<PathBuf>,
+    binds_selected: Option<String>,
+    binds_selected_command: String,
+    viewmodel_library: Vec<ViewmodelProfile>,
+    viewmodel_active: ViewmodelProfile,
+    viewmodel_selected: Option<usize>,
+    viewmodel_targets: BTreeSet<usize>,
+    sens_source: Option<Sensitivity>,
+    sens_dpi: f32,
+    sens_from_game: sensitivity::Game,
+    sens_from_value: f32,
+    sens_target: Sensitivity,
+    sens_targets: BTreeSet<usize>,
+    convar_profiles: Vec<CategoryState>,
+    lint_report: Option<lint::Report>,
 }
 
 impl Default for CS2ConfigApp {
@@ -122,8 +241,78 @@ impl Default for CS2ConfigApp {
                 size: 5.0,
                 name: "Default".to_string(),
                 original_code: None,
+                tags: Vec::new(),
+                folder: String::new(),
+                favorite: false,
+                added_at: 0,
             },
             crosshair_code_input: String::new(),
+            library_search: String::new(),
+            library_sort: LibrarySort::Name,
+            library_favorites_only: false,
+            library_tags_input: String::new(),
+            library_folder_input: String::new(),
+            pack_selection: BTreeSet::new(),
+            pack_path: "crosshair_pack.json".to_string(),
+            pack_author: String::new(),
+            pack_description: String::new(),
+            pending_import: None,
+            bulk_text: String::new(),
+            bulk_path: String::new(),
+            bulk_results: Vec::new(),
+            preview_resolution: render::COMMON_RESOLUTIONS[0],
+            preview_zoom: 1.0,
+            preview_texture: None,
+            preview_state: SpreadState::Standing,
+            preview_cycle: false,
+            preview_spread: 0.0,
+            export_options: ExportOptions::default(),
+            export_dir: "crosshair_png".to_string(),
+            export_bg_path: String::new(),
+            settings: AppSettings::default(),
+            preview_bg_input: String::new(),
+            preview_background: None,
+            preview_background_failed: None,
+            compare_a: None,
+            compare_b: None,
+            compare_overlay: false,
+            compare_opacity: 0.5,
+            compare_textures: [None, None],
+            similar_code_input: String::new(),
+            similar_results: Vec::new(),
+            history: History::default(),
+            preview_vision: ColorVision::Normal,
+            preview_resolution_input: String::new(),
+            preview_monitor: render::COMMON_RESOLUTIONS[0],
+            preview_aspect: AspectMode::Native,
+            preview_grid: false,
+            vcfg_diff: None,
+            vcfg_editor: None,
+            vcfg_editor_filter: String::new(),
+            apply_convars_file: None,
+            apply_autoexec: false,
+            binds: None,
+            binds_filter: String::new(),
+            binds_new_key: String::new(),
+            binds_new_command: String::new(),
+            binds_keyboard: true,
+            binds_compare: None,
+            binds_compare_set: None,
+            binds_compare_failed: None,
+            binds_selected: None,
+            binds_selected_command: String::new(),
+            viewmodel_library: Vec::new(),
+            viewmodel_active: ViewmodelProfile::default(),
+            viewmodel_selected: None,
+            viewmodel_targets: BTreeSet::new(),
+            sens_source: None,
+            sens_dpi: 800.0,
+            sens_from_game: sensitivity::Game::Valorant,
+            sens_from_value: 0.3,
+            sens_target: Sensitivity::default(),
+            sens_targets: BTreeSet::new(),
+            convar_profiles: Vec::new(),
+            lint_report: None,
         }
     }
 }
@@ -138,6 +327,10 @@ impl CS2ConfigApp {
         });
         app.load_steam_data();
         app.load_crosshair_profiles();
+        app.viewmodel_library = viewmodel::load_library();
+        app.convar_profiles = CATEGORIES.iter().map(CategoryState::load).collect();
+        app.settings = AppSettings::load();
+        app.sync_preview_background();
         app
     }
 
@@ -216,7 +409,7 @@ impl CS2ConfigApp {
             if let Ok(entries) = fs::read_dir(&cs2_config_path) {
                 for entry in entries.flatten() {
                     if let Some(file_name) = entry.file_name().to_str() {
-                        if file_name.ends_with(".cfg") || file_name.ends_with(".txt") {
+                        if file_name.ends_with(".cfg") || file_name.ends_with(".txt") || file_name.ends_with(".vcfg") {
                             config_files.push(file_name.to_string());
                         }
                     }
@@ -224,6 +417,7 @@ impl CS2ConfigApp {
             }
             config_files.sort();
         }
+        let settings_files = if has_cs2_config { vcfg::discover(&cs2_config_path) } else { Vec::new() };
         let name = self.get_account_name(account_path);
         Ok(SteamAccount {
             id: account_id.to_string(),
@@ -231,6 +425,7 @@ impl CS2ConfigApp {
             cs2_config_path: if has_cs2_config { Some(cs2_config_path) } else { None },
             has_cs2_config,
             config_files,
+            settings_files,
         })
     }
 
@@ -277,17 +472,13 @@ impl CS2ConfigApp {
             progress: 0.0,
             status: "Starting copy operation...".to_string(),
         });
-        let result = self.perform_copy(&source_config, from_idx, to_idx, backup);
+        let result = self.perform_copy(&source_config, to_idx, backup);
         match result {
             Ok(_) => {
                 self.success_message = format!("Successfully copied CS2 config from {} to {}", source.name.as_deref().unwrap_or(&source.id), target.name.as_deref().unwrap_or(&target.id));
                 self.state = AppState::Ready;
                 self.copy_operation = None;
-                if let Some(steam_path) = &self.steam_path.clone() {
-                    if let Ok(accounts) = self.scan_accounts(steam_path) {
-                        self.accounts = accounts;
-                    }
-                }
+                self.rescan_accounts();
             }
             Err(e) => {
                 self.error_message = format!("Copy failed: {}", e);
@@ -297,7 +488,7 @@ impl CS2ConfigApp {
         }
     }
 
-    fn perform_copy(&mut self, source_config: &Path, from_idx: usize, to_idx: usize, backup: bool) -> Result<(), String> {
+    fn perform_copy(&mut self, source_config: &Path, to_idx: usize, backup: bool) -> Result<(), String> {
         let target_account = &self.accounts[to_idx];
         let steam_path = self.steam_path.as_ref().ok_or("No Steam path")?;
         let target_config = if let Some(ref existing_path) = target_account.cs2_config_path {
@@ -315,7 +506,7 @@ impl CS2ConfigApp {
             let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
             let backup_path = target_config.with_extension(format!("backup.{}", timestamp));
             if let Some(ref mut op) = self.copy_operation { op.progress = 0.3; op.status = format!("Creating backup at {}...", backup_path.display()); }
-            self.copy_dir_recursive(source_config, &backup_path)?;
+            self.copy_dir_recursive(&target_config, &backup_path)?;
         }
         if !target_config.exists() { fs::create_dir_all(&target_config).map_err(|e| e.to_string())?; }
         if let Some(ref mut op) = self.copy_operation { op.progress = 0.5; op.status = "Copying configuration files...".to_string(); }
@@ -339,12 +530,429 @@ impl CS2ConfigApp {
         Ok(())
     }
 
+    fn rescan_accounts(&mut self) {
+        if let Some(steam_path) = self.steam_path.clone() {
+            if let Ok(accounts) = self.scan_accounts(&steam_path) {
+                self.accounts = accounts;
+            }
+        }
+        // Files may have been written, the compare bindings are read again when next shown
+        self.binds_compare_set = None;
+        self.binds_compare_failed = None;
+    }
+
+    /// Settings files of the source account, compared against the selected target account.
+    fn settings_files_ui(&mut self, ui: &mut egui::Ui, source_idx: usize) {
+        let source = self.accounts[source_idx].clone();
+        let Some(source_dir) = source.cs2_config_path.clone() else { return };
+        let target = self.selected_target.filter(|&idx| idx != source_idx).and_then(|idx| self.accounts.get(idx).cloned());
+        if source.settings_files.is_empty() {
+            ui.small("No .vcfg settings files found.");
+        }
+        let mut copied = false;
+        egui::Grid::new("vcfg_files").striped(true).num_columns(4).show(ui, |ui| {
+            for file in &source.settings_files {
+                ui.monospace(&file.name).on_hover_text(file.kind.purpose());
+                let mut kind = match file.slot {
+                    Some(slot) => format!("{} (slot {})", file.kind.label(), slot),
+                    None => file.kind.label().to_string(),
+                };
+                if let Some(entries) = file.entries {
+                    kind.push_str(&format!(", {} entries", entries));
+                }
+                ui.label(kind);
+                ui.label(format!("{:.1} KB", file.size as f64 / 1024.0));
+                ui.horizontal(|ui| {
+                    if ui.small_button("💾 Backup").clicked() {
+                        match vcfg::backup_file(&source_dir.join(&file.name)) {
+                            Ok(path) => self.success_message = format!("Backed up to {}", path.display()),
+                            Err(e) => self.error_message = e,
+                        }
+                    }
+                    if ui.small_button("📝 Edit").clicked() {
+                        let path = source_dir.join(&file.name);
+                        match vcfg::read_document(&path) {
+                            Ok(document) => self.vcfg_editor = Some((path, document)),
+                            Err(e) => self.error_message = format!("Not editable: {}", e),
+                        }
+                    }
+                    let Some(target) = &target else { return };
+                    let Some(target_dir) = &target.cs2_config_path else { return };
+                    ui.small(file.status(&target.settings_files));
+                    if ui.small_button("🔍 Diff").clicked() {
+                        self.vcfg_diff = Some((file.name.clone(), vcfg::diff_files(&file.name, &source_dir, target_dir)));
+                    }
+                    if ui.small_button("📋 Copy to target").clicked() {
+                        match vcfg::copy_file(&file.name, &source_dir, target_dir, self.create_backup) {
+                            Ok(backup) => {
+                                self.success_message = match backup {
+                                    Some(path) => format!("Copied {} (backup at {})", file.name, path.display()),
+                                    None => format!("Copied {}", file.name),
+                                };
+                                self.vcfg_diff = None;
+                                copied = true;
+                            }
+                            Err(e) => self.error_message = e,
+                        }
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        if target.is_none() {
+            ui.small("Select a target account to diff or copy individual files.");
+        }
+        if copied {
+            self.rescan_accounts();
+        }
+
+        let mut close = false;
+        if let Some((name, diff)) = &self.vcfg_diff {
+            ui.separator();
+            ui.horizontal(|ui| {
+                let changes = diff.iter().filter(|l| l.kind != DiffKind::Same).count();
+                ui.strong(format!("{}: source → target, {} changed lines", name, changes));
+                close = ui.small_button("✖").clicked();
+            });
+            egui::ScrollArea::vertical().id_source("vcfg_diff").max_height(240.0).show(ui, |ui| {
+                for line in diff.iter().filter(|l| l.kind != DiffKind::Same) {
+                    let (prefix, color) = match line.kind {
+                        DiffKind::Removed => ("- ", egui::Color32::from_rgb(230, 110, 110)),
+                        DiffKind::Added => ("+ ", egui::Color32::from_rgb(110, 200, 110)),
+                        DiffKind::Same => ("  ", ui.visuals().text_color()),
+                    };
+                    ui.label(egui::RichText::new(format!("{}{}", prefix, line.text)).monospace().color(color));
+                }
+            });
+        }
+        if close {
+            self.vcfg_diff = None;
+        }
+        self.vcfg_editor_ui(ui);
+    }
+
+    /// Edits the string entries of a KV3 settings file, one section (e.g. `convars`) at a time.
+    fn vcfg_editor_ui(&mut self, ui: &mut egui::Ui) {
+        let Some((path, document)) = &mut self.vcfg_editor else { return };
+        ui.separator();
+        let (mut save, mut close) = (false, false);
+        ui.horizontal(|ui| {
+            ui.strong(format!("📝 {}", path.file_name().and_then(|n| n.to_str()).unwrap_or_default()));
+            ui.add(egui::TextEdit::singleline(&mut self.vcfg_editor_filter).desired_width(160.0).hint_text("Filter keys"));
+            save = ui.button("💾 Save").on_hover_text("Writes the changed values back; comments and formatting of the file are kept").clicked();
+            close = ui.small_button("✖").clicked();
+        });
+        let filter = self.vcfg_editor_filter.to_lowercase();
+        egui::ScrollArea::vertical().id_source("vcfg_editor").max_height(240.0).show(ui, |ui| {
+            let Some(root) = document.root.as_object_mut() else { return };
+            for (section, value) in root.iter_mut() {
+                let Some(entries) = value.as_object_mut() else { continue };
+                let is_convars = section == "convars";
+                let mut rows: Vec<(&str, &mut String, Option<&convars::ConvarInfo>)> = entries.iter_mut()
+                    .filter(|(key, _)| filter.is_empty() || key.to_lowercase().contains(&filter))
+                    .filter_map(|(key, value)| match value {
+                        kv3::Value::String(text) => Some((key, text, is_convars.then(|| convars::lookup(key)).flatten())),
+                        _ => None,
+                    })
+                    .collect();
+                // Group convars by category, ones missing from the database last; file order is
+                // kept inside a group. The database only covers common convars, so a missing
+                // entry is not a warning.
+                if is_convars {
+                    rows.sort_by_key(|(_, _, info)| (info.is_none(), info.map(|i| i.category)));
+                }
+                egui::Grid::new(format!("vcfg_editor_{}", section)).striped(true).num_columns(3).show(ui, |ui| {
+                    let mut group = None;
+                    for (key, text, info) in rows {
+                        let category = info.map(|i| i.category.label()).unwrap_or("Not in database");
+                        if is_convars && group != Some(category) {
+                            ui.strong(category);
+                            ui.end_row();
+                            group = Some(category);
+                        }
+                        let label = ui.monospace(format!("{}/{}", section, key));
+                        match info {
+                            Some(info) => { label.on_hover_text(info.describe()); }
+                            None if is_convars => { label.on_hover_text("Not in the convar database"); }
+                            None => {}
+                        }
+                        if ui.text_edit_singleline(text).lost_focus() {
+                            if let Some(clamped) = info.and_then(|i| i.clamp(text)) {
+                                *text = clamped;
+                            }
+                        }
+                        let warning = match info {
+                            Some(info) if info.removed.is_some() => Some("removed in CS2".to_string()),
+                            Some(info) => info.check(text),
+                            None => None,
+                        };
+                        match warning {
+                            Some(warning) => { ui.colored_label(egui::Color32::from_rgb(230, 170, 60), format!("⚠ {}", warning)); }
+                            None => { ui.label(""); }
+                        }
+                        ui.end_row();
+                    }
+                });
+            }
+        });
+        if save {
+            match vcfg::write_document(path, document, self.create_backup) {
+                Ok(backup) => self.success_message = match backup {
+                    Some(backup) => format!("Saved {} (backup at {})", path.display(), backup.display()),
+                    None => format!("Saved {}", path.display()),
+                },
+                Err(e) => self.error_message = e,
+            }
+        }
+        if close {
+            self.vcfg_editor = None;
+        }
+    }
+
+    /// Key bindings of the source account: search, add, remove and rebind, plus copying
+    /// only the binds to the target account.
+    fn bindings_ui(&mut self, ui: &mut egui::Ui, source_idx: usize) {
+        let source = self.accounts[source_idx].clone();
+        let Some(source_dir) = source.cs2_config_path.clone() else { return };
+        let Some(file) = binds::keys_file(&source.settings_files) else {
+            ui.small("No key bindings file (cs2_user_keys_*.vcfg) found.");
+            return;
+        };
+        let path = source_dir.join(&file.name);
+        if self.binds.as_ref().map(|set| &set.path) != Some(&path) {
+            match BindSet::open(path.clone()) {
+                Ok(set) => self.binds = Some(set),
+                Err(e) => {
+                    ui.colored_label(egui::Color32::from_rgb(230, 110, 110), e);
+                    return;
+                }
+            }
+        }
+        let target = self.selected_target.filter(|&idx| idx != source_idx).and_then(|idx| self.accounts.get(idx).cloned());
+        let compare_path = self.binds_compare.filter(|&idx| idx != source_idx).and_then(|idx| {
+            let account = self.accounts.get(idx)?;
+            Some(account.cs2_config_path.as_ref()?.join(&binds::keys_file(&account.settings_files)?.name))
+        });
+        match compare_path {
+            Some(path) if self.binds_compare_set.as_ref().map(|set| &set.path) != Some(&path) && self.binds_compare_failed.as_ref() != Some(&path) => {
+                match BindSet::open(path.clone()) {
+                    Ok(set) => {
+                        self.binds_compare_set = Some(set);
+                        self.binds_compare_failed = None;
+                    }
+                    Err(e) => {
+                        self.error_message = e;
+                        self.binds_compare_set = None;
+                        self.binds_compare_failed = Some(path);
+                    }
+                }
+            }
+            None => {
+                self.binds_compare_set = None;
+                self.binds_compare_failed = None;
+            }
+            _ => {}
+        }
+        let Some(set) = &mut self.binds else { return };
+
+        let (mut save, mut reload, mut copy) = (false, false, false);
+        ui.horizontal(|ui| {
+            ui.monospace(&file.name);
+            ui.label(format!("{} bound keys", set.bindings.len()));
+            ui.add(egui::TextEdit::singleline(&mut self.binds_filter).desired_width(160.0).hint_text("Search key or command"));
+            save = ui.button("💾 Save").clicked();
+            reload = ui.small_button("⟲ Reload").on_hover_text("Discard unsaved changes").clicked();
+            if target.as_ref().is_some_and(|t| t.cs2_config_path.is_some()) {
+                copy = ui.button("📋 Copy binds to target").on_hover_text("Copies the saved binds, replacing only the bindings of the target account").clicked();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.binds_keyboard, "Keyboard view");
+            if !self.binds_keyboard { return; }
+            let name = |idx: usize| {
+                let account = &self.accounts[idx];
+                account.name.clone().unwrap_or_else(|| account.id.clone())
+            };
+            egui::ComboBox::from_id_source("binds_compare")
+                .selected_text(self.binds_compare.filter(|&idx| idx != source_idx && idx < self.accounts.len()).map_or("Compare with…".to_string(), name))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.binds_compare, None, "No comparison");
+                    for idx in 0..self.accounts.len() {
+                        if idx != source_idx && binds::keys_file(&self.accounts[idx].settings_files).is_some() {
+                            ui.selectable_value(&mut self.binds_compare, Some(idx), name(idx));
+                        }
+                    }
+                });
+            if let Some(other) = &self.binds_compare_set {
+                ui.colored_label(egui::Color32::from_rgb(200, 120, 40), format!("{} keys differ", keyboard::differing_keys(set, other).len()));
+            }
+        });
+        if self.binds_keyboard {
+            let clicked = keyboard::keyboard_ui(ui, set, self.binds_compare_set.as_ref(), self.binds_selected.as_deref());
+            if let Some(key) = clicked {
+                self.binds_selected_command = set.command(key).unwrap_or_default().to_string();
+                self.binds_selected = Some(key.to_string());
+            }
+            let off_layout: Vec<&str> = set.bindings.iter().map(|b| b.key.as_str()).filter(|key| !keyboard::is_drawn(key)).collect();
+            if !off_layout.is_empty() {
+                ui.small(format!("Also bound: {}", off_layout.join(", ")));
+            }
+            let mut close = false;
+            if let Some(key) = &self.binds_selected {
+                ui.horizontal(|ui| {
+                    ui.strong(format!("Rebind {}:", key));
+                    ui.add(egui::TextEdit::singleline(&mut self.binds_selected_command).desired_width(260.0).font(egui::TextStyle::Monospace).hint_text("Command"));
+                    if ui.button("✔ Apply").clicked() {
+                        match self.binds_selected_command.trim() {
+                            "" => set.unbind(key),
+                            command => set.bind(key, command),
+                        }
+                    }
+                    if ui.button("🗑 Unbind").clicked() {
+                        set.unbind(key);
+                        self.binds_selected_command.clear();
+                    }
+                    if let Some(other) = self.binds_compare_set.as_ref().and_then(|other| other.command(key)) {
+                        if ui.small_button("⬅ Take other").on_hover_text(other).clicked() {
+                            self.binds_selected_command = other.to_string();
+                        }
+                    }
+                    close = ui.small_button("✖").clicked();
+                });
+            }
+            if close {
+                self.binds_selected = None;
+            }
+        }
+
+        let filter = self.binds_filter.to_lowercase();
+        let mut remove = None;
+        let mut edited = false;
+        egui::ScrollArea::vertical().id_source("binds_table").max_height(260.0).show(ui, |ui| {
+            egui::Grid::new("binds_grid").striped(true).num_columns(3).show(ui, |ui| {
+                ui.strong("Key");
+                ui.strong("Command");
+                ui.end_row();
+                for (idx, binding) in set.bindings.iter_mut().enumerate() {
+                    if !filter.is_empty() && !binding.key.to_lowercase().contains(&filter) && !binding.command.to_lowercase().contains(&filter) { continue; }
+                    edited |= ui.add(egui::TextEdit::singleline(&mut binding.key).desired_width(90.0).font(egui::TextStyle::Monospace)).on_hover_text("Type another key to rebind").changed();
+                    edited |= ui.add(egui::TextEdit::singleline(&mut binding.command).desired_width(260.0).font(egui::TextStyle::Monospace)).changed();
+                    if ui.small_button("🗑").on_hover_text("Unbind").clicked() {
+                        remove = Some(idx);
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        if let Some(idx) = remove {
+            set.bindings.remove(idx);
+            edited = true;
+        }
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.binds_new_key).desired_width(90.0).hint_text("Key"));
+            ui.add(egui::TextEdit::singleline(&mut self.binds_new_command).desired_width(260.0).hint_text("Command"));
+            let valid = !self.binds_new_key.trim().is_empty() && !self.binds_new_command.trim().is_empty();
+            if ui.add_enabled(valid, egui::Button::new("➕ Add bind")).clicked() {
+                set.bindings.push(Binding { key: self.binds_new_key.trim().to_string(), command: self.binds_new_command.trim().to_string() });
+                self.binds_new_key.clear();
+                self.binds_new_command.clear();
+                edited = true;
+            }
+        });
+        if edited {
+            set.refresh_conflicts();
+        }
+
+        if !set.conflicts.is_empty() {
+            ui.label(format!("⚠ {} conflicts:", set.conflicts.len()));
+            for conflict in &set.conflicts {
+                ui.small(format!("  • {}", conflict.message()));
+            }
+        }
+
+        if save {
+            match set.save(self.create_backup) {
+                Ok(backup) => self.success_message = match backup {
+                    Some(backup) => format!("Saved key bindings (backup at {})", backup.display()),
+                    None => "Saved key bindings".to_string(),
+                },
+                Err(e) => self.error_message = e,
+            }
+        }
+        if reload {
+            self.binds = None;
+        }
+        if copy {
+            let Some(target) = target else { return };
+            let Some(target_dir) = &target.cs2_config_path else { return };
+            let target_name = binds::keys_file(&target.settings_files).map_or(file.name.as_str(), |f| f.name.as_str());
+            match binds::copy_bindings(&path, &target_dir.join(target_name), self.create_backup) {
+                Ok(backup) => {
+                    self.success_message = match backup {
+                        Some(backup) => format!("Copied key bindings to the target (backup at {})", backup.display()),
+                        None => "Copied key bindings to the target".to_string(),
+                    };
+                    self.rescan_accounts();
+                }
+                Err(e) => self.error_message = e,
+            }
+        }
+    }
+
+    /// "Check config" for the source account: lint findings with file and line, exportable
+    /// as JSON.
+    fn lint_ui(&mut self, ui: &mut egui::Ui, source_idx: usize) {
+        let account = &self.accounts[source_idx];
+        let Some(cfg_dir) = account.cs2_config_path.clone() else {
+            ui.small("This account has no CS2 config folder.");
+            return;
+        };
+        let account_id = account.id.clone();
+        ui.horizontal(|ui| {
+            if ui.button("🩺 Check config").clicked() {
+                let game_cfg_dir = self.steam_path.as_deref().and_then(lint::game_cfg_dir);
+                self.lint_report = Some(lint::check_account(&account_id, &cfg_dir, game_cfg_dir.as_deref()));
+            }
+            let Some(report) = self.lint_report.as_ref().filter(|r| r.account == account_id) else { return };
+            ui.label(format!("{} warnings, {} notes", report.count(lint::Severity::Warning), report.count(lint::Severity::Info)));
+            if ui.button("💾 Export JSON").clicked() {
+                let path = PathBuf::from(format!("config_report_{}.json", account_id));
+                match report.to_json().and_then(|json| fs::write(&path, json).map_err(|e| e.to_string())) {
+                    Ok(()) => self.success_message = format!("Report written to {}", path.display()),
+                    Err(e) => self.error_message = format!("Could not write the report: {}", e),
+                }
+            }
+        });
+        let Some(report) = self.lint_report.as_ref().filter(|r| r.account == account_id) else { return };
+        if report.findings.is_empty() {
+            ui.colored_label(egui::Color32::from_rgb(110, 200, 110), "✔ No problems found");
+            return;
+        }
+        egui::ScrollArea::vertical().id_source("lint_findings").max_height(260.0).show(ui, |ui| {
+            egui::Grid::new("lint_grid").striped(true).num_columns(2).show(ui, |ui| {
+                for finding in &report.findings {
+                    let location = match finding.line {
+                        Some(line) => format!("{}:{}", finding.file, line),
+                        None => finding.file.clone(),
+                    };
+                    ui.monospace(location);
+                    match finding.severity {
+                        lint::Severity::Warning => ui.colored_label(egui::Color32::from_rgb(230, 170, 60), format!("⚠ {}", finding.message)),
+                        lint::Severity::Info => ui.label(format!("ℹ {}", finding.message)),
+                    };
+                    ui.end_row();
+                }
+            });
+        });
+    }
+
     fn get_filtered_accounts(&self) -> Vec<(usize, SteamAccount)> {
         self.accounts.iter().enumerate().filter(|(_, account)| {
             if self.show_only_with_configs && !account.has_cs2_config { return false; }
             if self.search_filter.is_empty() { return true; }
             let filter = self.search_filter.to_lowercase();
-            account.id.to_lowercase().contains(&filter) || account.name.as_ref().map_or(false, |n| n.to_lowercase().contains(&filter))
+            account.id.to_lowercase().contains(&filter) || account.name.as_ref().is_some_and(|n| n.to_lowercase().contains(&filter))
         }).map(|(idx, account)| (idx, account.clone())).collect()
     }
 
@@ -366,167 +974,962 @@ impl CS2ConfigApp {
         }
     }
 
-    fn apply_crosshair_to_config(&self, profile: &CrosshairProfile, config_path: &Path) {
-        if let Ok(mut content) = fs::read_to_string(config_path) {
-            let commands = format!(
-                "cl_crosshairgap {}\ncl_crosshair_outlinethickness {}\ncl_crosshaircolor_r {}\ncl_crosshaircolor_g {}\ncl_crosshaircolor_b {}\ncl_crosshairalpha {}\ncl_crosshair_dynamic_splitdist {}\ncl_crosshair_recoil {}\ncl_fixedcrosshairgap {}\ncl_crosshaircolor {}\ncl_crosshair_drawoutline {}\ncl_crosshair_dynamic_splitalpha_innermod {}\ncl_crosshair_dynamic_splitalpha_outermod {}\ncl_crosshair_dynamic_maxdist_splitratio {}\ncl_crosshairthickness {}\ncl_crosshairstyle {}\ncl_crosshairdot {}\ncl_crosshairgap_useweaponvalue {}\ncl_crosshairusealpha {}\ncl_crosshair_t {}\ncl_crosshairsize {}",
-                profile.gap, profile.outline_thickness, profile.red, profile.green, profile.blue, profile.alpha,
-                profile.dynamic_splitdist, profile.recoil, profile.fixed_gap, profile.color, profile.draw_outline,
-                profile.dynamic_splitalpha_innermod, profile.dynamic_splitalpha_outermod, profile.dynamic_maxdist_split_ratio,
-                profile.thickness, profile.style, profile.dot, profile.gap_use_weapon_value, profile.use_alpha, profile.t,
-                profile.size
-            );
-            if !content.contains("cl_crosshairgap") {
-                content.push_str(&format!("\n{}", commands));
+    /// Writes the active crosshair into the target account's user convars vcfg.
+    fn apply_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(account) = self.selected_target.and_then(|idx| self.accounts.get(idx)).cloned() else { return };
+        let Some(cfg_dir) = account.cs2_config_path.clone() else { return };
+        let convars_files: Vec<String> = account.settings_files.iter().filter(|f| f.kind == vcfg::VcfgKind::UserConvars).map(|f| f.name.clone()).collect();
+        let default_file = apply::user_convars_file(&account.settings_files).map_or_else(|| "cs2_user_convars_0_slot0.vcfg".to_string(), |f| f.name.clone());
+        let mut file_name = self.apply_convars_file.clone().filter(|name| convars_files.contains(name)).unwrap_or(default_file);
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Apply to");
+            ui.strong(account.name.as_deref().unwrap_or(&account.id));
+            egui::ComboBox::from_id_source("apply_convars_file")
+                .selected_text(&file_name)
+                .show_ui(ui, |ui| {
+                    for name in &convars_files {
+                        ui.selectable_value(&mut file_name, name.clone(), name);
+                    }
+                });
+            ui.checkbox(&mut self.apply_autoexec, "Also write autoexec.cfg").on_hover_text("Fallback for setups that exec a config at launch (+exec autoexec)");
+            if ui.button("🎯 Apply Crosshair").clicked() {
+                let (mut written, mut failed) = (Vec::new(), Vec::new());
+                match apply::apply_to_vcfg(&cfg_dir.join(&file_name), &self.active_profile, self.create_backup) {
+                    Ok(Some(backup)) => written.push(format!("{} (backup at {})", file_name, backup.display())),
+                    Ok(None) => written.push(file_name.clone()),
+                    Err(e) => failed.push(format!("{}: {}", file_name, e)),
+                }
+                if self.apply_autoexec {
+                    match apply::write_autoexec(&cfg_dir, &self.active_profile, self.create_backup) {
+                        Ok(path) => written.push(path.display().to_string()),
+                        Err(e) => failed.push(format!("autoexec.cfg: {}", e)),
+                    }
+                }
+                if !written.is_empty() {
+                    self.success_message = format!("Crosshair written to {}", written.join(" and "));
+                }
+                if !failed.is_empty() {
+                    self.error_message = format!("Apply failed for {}", failed.join("; "));
+                }
+                self.rescan_accounts();
             }
-            let _ = fs::write(config_path, content);
+        });
+        self.apply_convars_file = Some(file_name);
+    }
+
+    /// The user convars vcfg of an account, or the slot 0 name the game would create.
+    fn user_convars_path(account: &SteamAccount) -> Option<PathBuf> {
+        let cfg_dir = account.cs2_config_path.as_ref()?;
+        let name = apply::user_convars_file(&account.settings_files).map_or("cs2_user_convars_0_slot0.vcfg", |f| f.name.as_str());
+        Some(cfg_dir.join(name))
+    }
+
+    /// Viewmodel library, editor with schematic, import from the source account and apply
+    /// to any number of accounts.
+    fn viewmodel_ui(&mut self, ui: &mut egui::Ui) {
+        let source = self.selected_source.and_then(|idx| self.accounts.get(idx));
+        let (success_message, error_message) = (&mut self.success_message, &mut self.error_message);
+        let label = |profile: &ViewmodelProfile| format!("{} (FOV {}, {} / {} / {}{})", profile.name, profile.fov, profile.offset_x, profile.offset_y, profile.offset_z, if profile.right_hand { "" } else { ", left hand" });
+        let changed = convar_profiles::profile_library_ui(ui, &mut self.viewmodel_library, &mut self.viewmodel_selected, &mut self.viewmodel_active, label, |ui, active, selected| {
+            if let Some(path) = source.and_then(Self::user_convars_path) {
+                if ui.button("📥 Import from Source").on_hover_text(path.display().to_string()).clicked() {
+                    let name = format!("Imported_{}", source.map_or("", |a| a.name.as_deref().unwrap_or(&a.id)));
+                    match vcfg::read_document(&path) {
+                        Ok(document) => {
+                            *active = ViewmodelProfile::from_document(&document, name);
+                            *selected = None;
+                            *success_message = "Viewmodel imported from the source account".to_string();
+                        }
+                        Err(e) => *error_message = e,
+                    }
+                }
+            }
+        });
+        if changed {
+            viewmodel::save_library(&self.viewmodel_library);
+        }
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| { viewmodel::editor_ui(ui, &mut self.viewmodel_active); });
+            viewmodel::schematic_ui(ui, &self.viewmodel_active);
+        });
+
+        if Self::account_checklist(ui, &self.accounts, &mut self.viewmodel_targets, "🔫 Apply Viewmodel") {
+            let (targets, convars) = (self.viewmodel_targets.clone(), self.viewmodel_active.convars());
+            self.apply_convars_to(&targets, &convars, "Viewmodel");
         }
     }
 
-    fn parse_crosshair_code(&mut self, code: &str) -> Option<CrosshairProfile> {
-        const DICTIONARY: &str = "ABCDEFGHJKLMNOPQRSTUVWXYZabcdefhijkmnopqrstuvwxyz23456789";
-        const DICTIONARY_LENGTH: u64 = 57;
+    /// Checkboxes for every account with a CS2 config plus an apply button. Returns true
+    /// when the button was clicked.
+    fn account_checklist(ui: &mut egui::Ui, accounts: &[SteamAccount], selection: &mut BTreeSet<usize>, action: &str) -> bool {
+        ui.label("Apply to accounts:");
+        ui.horizontal_wrapped(|ui| {
+            for (idx, account) in accounts.iter().enumerate().filter(|(_, a)| a.has_cs2_config) {
+                let mut checked = selection.contains(&idx);
+                if ui.checkbox(&mut checked, account.name.as_deref().unwrap_or(&account.id)).changed() {
+                    if checked { selection.insert(idx); } else { selection.remove(&idx); }
+                }
+            }
+        });
+        selection.retain(|&idx| idx < accounts.len());
+        let label = format!("{} to {} account{}", action, selection.len(), if selection.len() == 1 { "" } else { "s" });
+        ui.add_enabled(!selection.is_empty(), egui::Button::new(label)).clicked()
+    }
 
-        if !code.starts_with("CSGO-") || code.matches('-').count() != 5 {
-            eprintln!("Invalid code format: {}", code);
-            return None;
+    /// Writes `convars` into the user convars vcfg of every account in `targets`, following
+    /// the backup setting of config copies.
+    fn apply_convars_to(&mut self, targets: &BTreeSet<usize>, convars: &[(&str, String)], what: &str) {
+        let (mut applied, mut failed) = (0, Vec::new());
+        for account in targets.iter().filter_map(|&idx| self.accounts.get(idx)) {
+            let Some(path) = Self::user_convars_path(account) else { continue };
+            match apply::write_convars(&path, convars, self.create_backup) {
+                Ok(_) => applied += 1,
+                Err(e) => failed.push(format!("{}: {}", account.id, e)),
+            }
         }
-        let parts: Vec<&str> = code.split('-').collect();
-        if parts.len() != 6 || parts[0] != "CSGO" {
-            eprintln!("Invalid parts: {:?}", parts);
-            return None;
+        if failed.is_empty() {
+            self.success_message = format!("{} applied to {} account(s){}", what, applied, if self.create_backup { " with backups" } else { "" });
+        } else {
+            self.error_message = format!("{} apply failed for {}", what, failed.join("; "));
+        }
+        self.rescan_accounts();
+    }
+
+    /// Mouse settings of the source account, eDPI and cm/360 for a DPI, conversion from
+    /// other games and apply to any number of accounts.
+    fn sensitivity_ui(&mut self, ui: &mut egui::Ui) {
+        let source = self.selected_source.and_then(|idx| self.accounts.get(idx));
+        let source_path = source.and_then(Self::user_convars_path);
+        ui.horizontal(|ui| {
+            ui.label("Mouse DPI:");
+            ui.add(egui::DragValue::new(&mut self.sens_dpi).range(100.0..=32000.0).speed(50.0));
+            if let Some(path) = &source_path {
+                if ui.button("📥 Read from Source").on_hover_text(path.display().to_string()).clicked() {
+                    match vcfg::read_document(path) {
+                        Ok(document) => {
+                            let current = Sensitivity::from_document(&document);
+                            self.sens_source = Some(current);
+                            self.sens_target = current;
+                        }
+                        Err(e) => self.error_message = e,
+                    }
+                }
+            }
+        });
+        let yaw = self.sens_source.map_or(sensitivity::SOURCE_YAW, |s| s.yaw());
+        if let Some(current) = self.sens_source {
+            ui.label(format!(
+                "Source: sensitivity {}, zoom ratio {}, m_yaw {}, m_pitch {} → eDPI {:.0}, {:.1} cm/360",
+                current.sensitivity, current.zoom_ratio, current.m_yaw, current.m_pitch,
+                sensitivity::edpi(current.sensitivity, self.sens_dpi),
+                sensitivity::cm_per_360(current.sensitivity, current.yaw(), self.sens_dpi),
+            ));
+            if (current.m_yaw - current.m_pitch).abs() > f32::EPSILON {
+                ui.colored_label(egui::Color32::from_rgb(230, 170, 60), "⚠ m_yaw and m_pitch differ, vertical and horizontal speed are not equal");
+            }
         }
-        let chars: String = parts[1..].join("");
-        if chars.len() != 25 {
-            eprintln!("Invalid character length: {}", chars.len());
+
+        ui.horizontal(|ui| {
+            ui.label("Convert from");
+            egui::ComboBox::from_id_source("sens_from_game")
+                .selected_text(self.sens_from_game.label())
+                .show_ui(ui, |ui| {
+                    for game in sensitivity::Game::ALL {
+                        ui.selectable_value(&mut self.sens_from_game, game, game.label());
+                    }
+                });
+            ui.add(egui::DragValue::new(&mut self.sens_from_value).range(0.001..=100.0).speed(0.01).max_decimals(4));
+            let converted = sensitivity::convert(self.sens_from_value, self.sens_from_game.yaw(), yaw);
+            ui.label(format!("= {:.4} in CS2", converted));
+            if ui.button("Use").clicked() {
+                self.sens_target.sensitivity = (converted * 10_000.0).round() / 10_000.0;
+            }
+        });
+
+        egui::Grid::new("sens_target").num_columns(2).show(ui, |ui| {
+            ui.label("Sensitivity");
+            ui.add(egui::DragValue::new(&mut self.sens_target.sensitivity).range(0.0001..=8.0).speed(0.005).max_decimals(4));
+            ui.end_row();
+            ui.label("Zoom ratio");
+            ui.add(egui::DragValue::new(&mut self.sens_target.zoom_ratio).range(0.0..=2.0).speed(0.005).max_decimals(4));
+            ui.end_row();
+        });
+        ui.label(format!(
+            "eDPI {:.0}, {:.1} cm/360",
+            sensitivity::edpi(self.sens_target.sensitivity, self.sens_dpi),
+            sensitivity::cm_per_360(self.sens_target.sensitivity, yaw, self.sens_dpi),
+        ));
+
+        if Self::account_checklist(ui, &self.accounts, &mut self.sens_targets, "🖱 Apply Sensitivity") {
+            let (targets, convars) = (self.sens_targets.clone(), self.sens_target.convars());
+            self.apply_convars_to(&targets, &convars, "Sensitivity");
+        }
+    }
+
+    /// Library, editor, import and apply for one of the [`CATEGORIES`] of convar profiles.
+    fn convar_profile_ui(&mut self, ui: &mut egui::Ui, idx: usize) {
+        let category = &CATEGORIES[idx];
+        let source = self.selected_source.and_then(|idx| self.accounts.get(idx)).cloned();
+        let Some(state) = self.convar_profiles.get_mut(idx) else { return };
+        let error_message = &mut self.error_message;
+        let changed = convar_profiles::profile_library_ui(ui, &mut state.library, &mut state.selected, &mut state.active, |profile| profile.name.clone(), |ui, active, selected| {
+            if ui.button("↺ Defaults").clicked() {
+                *active = ConvarProfile::new(category, active.name.clone());
+            }
+            if let Some(path) = source.as_ref().and_then(Self::user_convars_path) {
+                if ui.button("📥 Import from Source").on_hover_text(path.display().to_string()).clicked() {
+                    match vcfg::read_document(&path) {
+                        Ok(document) => {
+                            let name = format!("Imported_{}", source.as_ref().map_or("", |a| a.name.as_deref().unwrap_or(&a.id)));
+                            *active = ConvarProfile::from_document(category, &document, name);
+                            *selected = None;
+                        }
+                        Err(e) => *error_message = e,
+                    }
+                }
+            }
+        });
+        if changed {
+            state.save(category);
+        }
+        convar_profiles::editor_ui(ui, category, &mut state.active);
+
+        if Self::account_checklist(ui, &self.accounts, &mut state.targets, &format!("Apply {}", category.title)) {
+            let (targets, convars) = (state.targets.clone(), state.active.convars(category));
+            self.apply_convars_to(&targets, &convars, category.title);
+        }
+    }
+
+    /// Decodes a share code. Invalid codes are rejected with the reason in the error message.
+    fn parse_crosshair_code(&mut self, code: &str) -> Option<CrosshairProfile> {
+        let bytes = match share_code::decode_bytes(code) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                self.error_message = format!("Invalid crosshair code: {}", e);
+                return None;
+            }
+        };
+        if !share_code::is_valid(code) {
+            self.error_message = "Invalid crosshair code: the checksum does not match".to_string();
             return None;
         }
+        let name = format!("Imported_{}", code.split('-').nth(1).unwrap_or_default());
+        Some(share_code::profile_from_bytes(&bytes, name, code))
+    }
 
-        let mut num = BigUint::zero();
-        let base = BigUint::from(DICTIONARY_LENGTH);
-        for (i, c) in chars.chars().rev().enumerate() {
-            let idx = match DICTIONARY.find(c) {
-                Some(idx) => idx as u64,
-                None => {
-                    eprintln!("Invalid character '{}' at position {}", c, i);
-                    return None;
+    fn generate_crosshair_code(&self, profile: &CrosshairProfile) -> String {
+        share_code::code(profile)
+    }
+}
+
+impl CS2ConfigApp {
+    fn library_folder_ui(&mut self, ui: &mut egui::Ui, path: &str, node: &FolderNode, to_delete: &mut Option<usize>) {
+        for (name, child) in &node.children {
+            let child_path = if path.is_empty() { name.clone() } else { format!("{}/{}", path, name) };
+            egui::CollapsingHeader::new(format!("📁 {} ({})", name, child.count()))
+                .id_source(format!("library_folder_{}", child_path))
+                .default_open(!self.library_search.is_empty())
+                .show(ui, |ui| self.library_folder_ui(ui, &child_path, child, to_delete));
+        }
+        for &idx in &node.entries {
+            self.library_row_ui(ui, idx, to_delete);
+        }
+    }
+
+    /// Key of the current user for per-user settings: the selected source account.
+    fn settings_user_key(&self) -> String {
+        self.selected_source.and_then(|idx| self.accounts.get(idx)).map_or_else(|| "default".to_string(), |a| a.id.clone())
+    }
+
+    fn current_background(&self) -> Option<&PreviewBackground> {
+        self.settings.preview_backgrounds.get(&self.settings_user_key())
+    }
+
+    /// Loads the background image remembered for the current user, if it is not loaded yet.
+    fn sync_preview_background(&mut self) {
+        let wanted = self.current_background().map(|bg| bg.path.clone());
+        if self.preview_background.as_ref().map(|(path, _)| path) == wanted.as_ref() { return; }
+        if wanted.is_some() && self.preview_background_failed == wanted { return; }
+        self.preview_background = None;
+        if let Some(path) = wanted {
+            self.preview_bg_input = path.clone();
+            match export::load_image(Path::new(&path)) {
+                Ok(image) => self.preview_background = Some((path, image)),
+                Err(e) => {
+                    self.error_message = e;
+                    self.preview_background_failed = Some(path);
                 }
+            }
+        }
+    }
+
+    fn set_preview_background(&mut self, path: Option<String>) {
+        let key = self.settings_user_key();
+        self.preview_background_failed = None;
+        match path {
+            Some(path) => {
+                self.settings.remember_background(&path);
+                self.settings.preview_backgrounds.insert(key, PreviewBackground::new(path));
+            }
+            None => { self.settings.preview_backgrounds.remove(&key); }
+        }
+        self.settings.save();
+        self.sync_preview_background();
+    }
+
+    fn preview_ui(&mut self, ui: &mut egui::Ui) {
+        let ctx = ui.ctx().clone();
+        ui.horizontal(|ui| {
+            ui.label("Crosshair Preview:");
+            ui.label("Game");
+            egui::ComboBox::from_id_source("preview_resolution")
+                .selected_text(self.preview_resolution.label())
+                .show_ui(ui, |ui| {
+                    for resolution in render::COMMON_RESOLUTIONS {
+                        ui.selectable_value(&mut self.preview_resolution, resolution, resolution.label());
+                    }
+                });
+            let custom = ui.add(egui::TextEdit::singleline(&mut self.preview_resolution_input).desired_width(80.0).hint_text("custom"));
+            if custom.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                match Resolution::parse(&self.preview_resolution_input) {
+                    Some(resolution) => self.preview_resolution = resolution,
+                    None => self.error_message = format!("Invalid resolution: {}", self.preview_resolution_input),
+                }
+            }
+            ui.label("on monitor");
+            egui::ComboBox::from_id_source("preview_monitor")
+                .selected_text(self.preview_monitor.label())
+                .show_ui(ui, |ui| {
+                    for resolution in render::COMMON_RESOLUTIONS {
+                        ui.selectable_value(&mut self.preview_monitor, resolution, resolution.label());
+                    }
+                });
+            egui::ComboBox::from_id_source("preview_aspect")
+                .selected_text(self.preview_aspect.label())
+                .show_ui(ui, |ui| {
+                    for mode in AspectMode::ALL {
+                        ui.selectable_value(&mut self.preview_aspect, mode, mode.label());
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.add(egui::Slider::new(&mut self.preview_zoom, 1.0..=16.0).step_by(1.0).text("Zoom"));
+            ui.checkbox(&mut self.preview_grid, "Pixel grid");
+            egui::ComboBox::from_id_source("preview_vision")
+                .selected_text(self.preview_vision.label())
+                .show_ui(ui, |ui| {
+                    for vision in ColorVision::ALL {
+                        ui.selectable_value(&mut self.preview_vision, vision, vision.label());
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            ui.label("Background:");
+            ui.add(egui::TextEdit::singleline(&mut self.preview_bg_input).hint_text("map screenshot (PNG/JPEG)"));
+            if ui.button("Load").clicked() {
+                self.set_preview_background(Some(self.preview_bg_input.trim().to_string()));
+            }
+            let recent = self.settings.recent_backgrounds.clone();
+            egui::ComboBox::from_id_source("preview_bg_recent")
+                .selected_text("Recent")
+                .show_ui(ui, |ui| {
+                    for path in recent {
+                        if ui.selectable_label(false, &path).clicked() {
+                            self.preview_bg_input = path.clone();
+                            self.set_preview_background(Some(path));
+                        }
+                    }
+                });
+            if self.current_background().is_some() && ui.button("✖ None").clicked() {
+                self.preview_bg_input.clear();
+                self.set_preview_background(None);
+            }
+            if self.preview_background_failed.is_some() {
+                ui.colored_label(egui::Color32::from_rgb(230, 170, 60), "⚠ Could not be loaded").on_hover_text("The path stays saved, press Load to try again");
+            }
+        });
+        self.sync_preview_background();
+        if render::is_dynamic_style(self.active_profile.style) {
+            ui.horizontal(|ui| {
+                ui.label("Simulate:");
+                for state in SpreadState::ALL {
+                    ui.selectable_value(&mut self.preview_state, state, state.label());
+                }
+                ui.checkbox(&mut self.preview_cycle, "Cycle states");
+            });
+            self.animate_preview_spread(&ctx);
+        } else {
+            self.preview_spread = 0.0;
+        }
+
+        let (width, height) = preview::pixels_for(&ctx, egui::vec2(ui.available_width(), 240.0), self.preview_zoom);
+        let mut buffer = RgbaBuffer::new(width, height, [60, 60, 60, 255]);
+        let view = self.current_background().cloned();
+        if let (Some((_, image)), Some(view)) = (&self.preview_background, &view) {
+            raster::draw_background(&mut buffer, image, view.pan, view.zoom);
+        }
+        // Contrast is measured on the background around the crosshair, before it is drawn
+        let scale = self.preview_aspect.scale(self.preview_resolution, self.preview_monitor);
+        let m = render::metrics(&self.active_profile, self.preview_resolution);
+        let reach = ((m.distance + m.bar_size + m.bar_thickness).max(4) as f32 * scale[0].max(scale[1])) as u32 + 4;
+        let (cx, cy) = (width / 2, height / 2);
+        let area = [cx.saturating_sub(reach), cy.saturating_sub(reach), cx + reach, cy + reach];
+        let contrast = vision::contrast_report(m.rgb, &buffer, area, self.preview_vision);
+        raster::draw_crosshair_scaled(&mut buffer, &self.active_profile, self.preview_resolution, scale, self.preview_spread);
+        vision::simulate_buffer(&mut buffer, self.preview_vision);
+        let response = preview::show_buffer(ui, &mut self.preview_texture, "crosshair_preview", &buffer, self.preview_zoom);
+        if self.preview_grid {
+            let size = [buffer.width, buffer.height];
+            preview::draw_pixel_grid(ui, response.rect, size, [1.0, 1.0], egui::Color32::from_black_alpha(90));
+            if scale != [1.0, 1.0] {
+                preview::draw_pixel_grid(ui, response.rect, size, scale, egui::Color32::from_rgba_unmultiplied(255, 200, 0, 110));
+            }
+        }
+        if scale != [1.0, 1.0] {
+            ui.small(format!(
+                "{} shown on {} ({}): one game pixel covers {:.2} x {:.2} monitor pixels{}",
+                self.preview_resolution.label(), self.preview_monitor.label(), self.preview_aspect.label(), scale[0], scale[1],
+                if self.preview_grid { ", game pixels outlined in yellow" } else { "" },
+            ));
+        }
+        if let Some(contrast) = contrast {
+            let color = match contrast.rating() {
+                ContrastRating::Good => egui::Color32::GREEN,
+                ContrastRating::Fair => egui::Color32::YELLOW,
+                ContrastRating::Poor => egui::Color32::RED,
             };
-            num = num * &base + BigUint::from(idx);
+            ui.colored_label(color, format!(
+                "Contrast {:.1}:1 ({}) — worst {:.1}:1, {:.0}% of the surrounding background below {}:1",
+                contrast.average, contrast.rating().label(), contrast.worst, contrast.low_share * 100.0, vision::LOW_CONTRAST,
+            ));
         }
 
-        let hexnum = format!("{:x}", num);
-        let padded_hex = format!("{:0>36}", hexnum);
-        let bytes: Vec<u8> = (0..padded_hex.len())
-            .step_by(2)
-            .map(|i| u8::from_str_radix(&padded_hex[i..i + 2], 16).unwrap_or(0))
-            .collect();
+        // Dragging pans and scrolling zooms the background; the crosshair stays at true pixel scale
+        if let Some(mut view) = view.filter(|_| self.preview_background.is_some()) {
+            let game_px_per_point = ctx.pixels_per_point() / self.preview_zoom;
+            let drag = response.drag_delta() * game_px_per_point / view.zoom;
+            let scroll = if response.hovered() { ctx.input(|i| i.raw_scroll_delta.y) } else { 0.0 };
+            if drag != egui::Vec2::ZERO || scroll != 0.0 {
+                view.pan = [view.pan[0] - drag.x, view.pan[1] - drag.y];
+                view.zoom = (view.zoom * (scroll * 0.002).exp()).clamp(0.1, 16.0);
+                self.settings.preview_backgrounds.insert(self.settings_user_key(), view);
+            }
+            if response.drag_stopped() || scroll != 0.0 {
+                self.settings.save();
+            }
+            ui.small(format!("Background zoom {:.2}x — drag to pan, scroll to zoom", self.current_background().map_or(1.0, |bg| bg.zoom)));
+        }
+    }
 
-        if bytes.len() < 18 {
-            eprintln!("Insufficient bytes: {}", bytes.len());
-            return None;
+    /// Eases the preview spread towards the selected movement state, like the game's
+    /// crosshair recovering between states.
+    fn animate_preview_spread(&mut self, ctx: &egui::Context) {
+        const CYCLE_SECONDS: f64 = 1.5;
+        const RECOVERY_RATE: f32 = 12.0;
+        let (time, dt) = ctx.input(|i| (i.time, i.stable_dt.min(0.1)));
+        if self.preview_cycle {
+            self.preview_state = SpreadState::ALL[(time / CYCLE_SECONDS) as usize % SpreadState::ALL.len()];
         }
+        let target = self.preview_state.spread(self.active_profile.style, time);
+        if self.preview_state == SpreadState::Firing {
+            // Every shot kicks the spread up instantly, recovery is eased below
+            self.preview_spread = self.preview_spread.max(target);
+        }
+        self.preview_spread += (target - self.preview_spread) * (1.0 - (-RECOVERY_RATE * dt).exp());
+        if self.preview_cycle || self.preview_state != SpreadState::Standing || self.preview_spread > 0.01 {
+            ctx.request_repaint();
+        } else {
+            self.preview_spread = 0.0;
+        }
+    }
 
-        let checksum = bytes[1..18]
-            .iter()
-            .fold(0u16, |acc, &b| acc.wrapping_add(b as u16)) as u8;
-        if bytes[0] != checksum {
-            eprintln!("Checksum mismatch: expected {}, got {}", checksum, bytes[0]);
-        }
-
-        Some(CrosshairProfile {
-            gap: (bytes[2] as i8) as f32 / 10.0,
-            outline_thickness: bytes[3] as f32 / 2.0,
-            red: bytes[4],
-            green: bytes[5],
-            blue: bytes[6],
-            alpha: bytes[7],
-            dynamic_splitdist: bytes[8] & 0x7f,
-            recoil: (bytes[8] >> 7) != 0,
-            fixed_gap: (bytes[9] as i8) as f32 / 10.0,
-            color: bytes[10] & 0x07,
-            draw_outline: (bytes[10] & 0x08) != 0,
-            dynamic_splitalpha_innermod: ((bytes[10] >> 4) as f32) / 10.0,
-            dynamic_splitalpha_outermod: ((bytes[11] & 0x0f) as f32) / 10.0,
-            dynamic_maxdist_split_ratio: ((bytes[11] >> 4) as f32) / 10.0,
-            thickness: bytes[12] as f32 / 10.0,
-            style: (bytes[13] & 0x0f) >> 1,
-            dot: (bytes[13] & 0x10) != 0,
-            gap_use_weapon_value: (bytes[13] & 0x20) != 0,
-            use_alpha: (bytes[13] & 0x40) != 0,
-            t: (bytes[13] & 0x80) != 0,
-            size: (((bytes[15] & 0x1f) as u16) << 8 | bytes[14] as u16) as f32 / 10.0,
-            name: format!("Imported_{}", parts[1]),
-            original_code: Some(code.to_string()),
-        })
+    fn snapshot(&self) -> Snapshot {
+        Snapshot { active_profile: self.active_profile.clone(), library: self.crosshair_library.clone() }
     }
 
-    fn signed_byte(x: u8) -> i8 {
-        ((x ^ 0x80u8) as i8) - (0x80u8 as i8)
+    /// Makes the current state undoable before an edit described by `label`.
+    fn remember(&mut self, label: impl Into<String>) {
+        let snapshot = self.snapshot();
+        self.history.record(label, snapshot);
     }
 
-    fn generate_crosshair_code(&self, profile: &CrosshairProfile) -> String {
-        if let Some(ref original_code) = profile.original_code {
-            return original_code.clone();
-        }
-
-        const DICTIONARY: &str = "ABCDEFGHJKLMNOPQRSTUVWXYZabcdefhijkmnopqrstuvwxyz23456789";
-        const DICTIONARY_LENGTH: u64 = 57;
-
-        let mut bytes = vec![
-            0, // Checksum placeholder
-            1, // Version/ID byte
-            ((profile.gap * 10.0) as i8) as u8,
-            (profile.outline_thickness * 2.0).min(255.0) as u8,
-            profile.red,
-            profile.green,
-            profile.blue,
-            profile.alpha,
-            profile.dynamic_splitdist | ((profile.recoil as u8) << 7),
-            ((profile.fixed_gap * 10.0) as i8) as u8,
-            (profile.color & 0x07) | ((profile.draw_outline as u8) << 3) | (((profile.dynamic_splitalpha_innermod * 10.0).min(15.0) as u8) << 4),
-            ((profile.dynamic_splitalpha_outermod * 10.0).min(15.0) as u8 & 0x0F) | (((profile.dynamic_maxdist_split_ratio * 10.0).min(15.0) as u8) << 4),
-            (profile.thickness * 10.0).min(255.0) as u8,
-            (profile.style << 1) |
-            ((profile.dot as u8) << 4) |
-            ((profile.gap_use_weapon_value as u8) << 5) |
-            ((profile.use_alpha as u8) << 6) |
-            ((profile.t as u8) << 7),
-            (profile.size * 10.0).min(65535.0) as u16 as u8,
-            (((profile.size * 10.0).min(65535.0) as u16) >> 8) as u8 & 0x1f,
-            0,
-            0,
-        ];
-
-        bytes[0] = bytes[1..]
-            .iter()
-            .fold(0u16, |acc, &b| acc.wrapping_add(b as u16)) as u8;
-
-        let mut num = BigUint::zero();
-        let base = BigUint::from(256u64);
-        for &byte in bytes.iter().rev() {
-            num = num * &base + BigUint::from(byte as u64);
-        }
-
-        let mut code = String::with_capacity(25);
-        let dict_base = BigUint::from(DICTIONARY_LENGTH);
-        if num.is_zero() {
-            code.push_str(&"a".repeat(25));
+    /// Drops everything that refers to library entries by index.
+    fn clear_library_selection(&mut self) {
+        self.selected_library_idx = None;
+        self.pack_selection.clear();
+        self.compare_a = None;
+        self.compare_b = None;
+        self.similar_results.clear();
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        let library_changed = snapshot.library.len() != self.crosshair_library.len()
+            || snapshot.library.iter().zip(&self.crosshair_library).any(|(a, b)| a.name != b.name || !share_code::same_settings(a, b));
+        self.active_profile = snapshot.active_profile;
+        self.crosshair_library = snapshot.library;
+        if library_changed {
+            self.clear_library_selection();
+        }
+        self.save_crosshair_profiles();
+    }
+
+    fn undo(&mut self) {
+        if let Some(snapshot) = self.history.undo(self.snapshot()) {
+            self.restore(snapshot);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(snapshot) = self.history.redo(self.snapshot()) {
+            self.restore(snapshot);
+        }
+    }
+
+    /// Ctrl+Z / Ctrl+Y (or Ctrl+Shift+Z), unless a text field has focus and handles them itself.
+    fn handle_history_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.memory(|m| m.focused().is_some()) { return; }
+        let redo = ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Y)
+            || i.consume_key(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z));
+        let undo = ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z));
+        if redo { self.redo(); } else if undo { self.undo(); }
+    }
+
+    fn analysis_ui(&mut self, ui: &mut egui::Ui) {
+        let scale = self.preview_aspect.scale(self.preview_resolution, self.preview_monitor);
+        let result = analysis::analyze(&self.active_profile, self.preview_resolution, scale);
+        ui.small(format!("At {} on {} ({}), in monitor pixels from the screen center:", self.preview_resolution.label(), self.preview_monitor.label(), self.preview_aspect.label()));
+        egui::Grid::new("analysis_extents").striped(true).num_columns(4).show(ui, |ui| {
+            ui.strong("Part");
+            ui.strong("Position");
+            ui.strong("Width");
+            ui.strong("Height");
+            ui.end_row();
+            for extent in &result.extents {
+                ui.label(&extent.part);
+                ui.monospace(format!("{:+.2}, {:+.2}", extent.x0, extent.y0));
+                ui.monospace(format!("{:.2}", extent.width()));
+                ui.monospace(format!("{:.2}", extent.height()));
+                ui.end_row();
+            }
+        });
+        if result.findings.is_empty() {
+            ui.colored_label(egui::Color32::GREEN, "✔ Pixel perfect: symmetric and on whole pixels");
+        }
+        for finding in &result.findings {
+            ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", finding));
+        }
+        for suggestion in &result.suggestions {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}: {} → {} ({})", suggestion.field.label(), suggestion.current, suggestion.suggested, suggestion.reason));
+                if ui.button("Apply").clicked() {
+                    self.remember(format!("Apply suggested {}", suggestion.field.label().to_lowercase()));
+                    suggestion.field.set(&mut self.active_profile, suggestion.suggested);
+                }
+            });
+        }
+    }
+
+    fn history_ui(&mut self, ui: &mut egui::Ui) {
+        if !self.history.can_undo() && !self.history.can_redo() {
+            ui.small("No edits yet.");
+            return;
+        }
+        // Clicking an entry undoes (or redoes) every step up to and including it
+        let (mut undo_steps, mut redo_steps) = (0, 0);
+        egui::ScrollArea::vertical().id_source("edit_history").max_height(150.0).show(ui, |ui| {
+            let redo: Vec<&str> = self.history.redo_labels().collect();
+            for (i, label) in redo.iter().enumerate().rev() {
+                if ui.add(egui::Label::new(egui::RichText::new(format!("↷ {}", label)).weak()).sense(egui::Sense::click())).on_hover_text("Redo up to here").clicked() {
+                    redo_steps = i + 1;
+                }
+            }
+            for (i, label) in self.history.undo_labels().enumerate() {
+                if ui.add(egui::Label::new(format!("↶ {}", label)).sense(egui::Sense::click())).on_hover_text("Undo up to here").clicked() {
+                    undo_steps = i + 1;
+                }
+            }
+        });
+        for _ in 0..undo_steps { self.undo(); }
+        for _ in 0..redo_steps { self.redo(); }
+    }
+
+    fn select_library_entry(&mut self, idx: usize) {
+        let Some(profile) = self.crosshair_library.get(idx).cloned() else { return };
+        self.remember(format!("Load \"{}\"", profile.name));
+        self.selected_library_idx = Some(idx);
+        self.library_folder_input = profile.folder.clone();
+        self.library_tags_input = profile.tags.join(", ");
+        self.active_profile = profile;
+    }
+
+    fn similar_ui(&mut self, ui: &mut egui::Ui) {
+        const MAX_RESULTS: usize = 10;
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.similar_code_input).desired_width(260.0).hint_text("Share code, empty for the active profile"));
+            if ui.button("🔎 Find similar").clicked() {
+                let code = self.similar_code_input.trim().to_string();
+                let target = if code.is_empty() {
+                    Some(self.active_profile.clone())
+                } else {
+                    self.parse_crosshair_code(&code)
+                };
+                if let Some(target) = target {
+                    self.similar_results = similarity::rank(&self.crosshair_library, &target, MAX_RESULTS);
+                }
+            }
+        });
+        if self.similar_results.is_empty() { return; }
+
+        let mut select = None;
+        egui::Grid::new("similar_results").striped(true).num_columns(4).show(ui, |ui| {
+            for &(idx, distance) in &self.similar_results {
+                let Some(profile) = self.crosshair_library.get(idx) else { continue };
+                let (rect, _) = ui.allocate_exact_size(egui::vec2(16.0, 16.0), egui::Sense::hover());
+                let [r, g, b] = render::crosshair_rgb(profile);
+                ui.painter().rect_filled(rect, 2.0, egui::Color32::from_rgb(r, g, b));
+                if ui.selectable_label(self.selected_library_idx == Some(idx), &profile.name).clicked() {
+                    select = Some(idx);
+                }
+                ui.label(format!("{:.0}% similar", similarity::similarity_percent(distance)));
+                if distance < similarity::NEAR_DUPLICATE_DISTANCE {
+                    ui.colored_label(egui::Color32::LIGHT_BLUE, "near duplicate");
+                } else {
+                    ui.label("");
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(idx) = select {
+            self.select_library_entry(idx);
+        }
+    }
+
+    /// Library entry `idx`, or the profile in the editor for `None`.
+    fn compare_profile(&self, idx: Option<usize>) -> CrosshairProfile {
+        idx.and_then(|idx| self.crosshair_library.get(idx)).cloned().unwrap_or_else(|| self.active_profile.clone())
+    }
+
+    fn compare_ui(&mut self, ui: &mut egui::Ui) {
+        let names: Vec<String> = self.crosshair_library.iter().map(|p| p.name.clone()).collect();
+        ui.horizontal(|ui| {
+            for (id, slot) in [("A:", &mut self.compare_a), ("B:", &mut self.compare_b)] {
+                ui.label(id);
+                let selected_text = slot.and_then(|idx| names.get(idx)).map_or("✏ Active profile", String::as_str);
+                egui::ComboBox::from_id_source(format!("compare_{}", id))
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(slot, None, "✏ Active profile");
+                        for (idx, name) in names.iter().enumerate() {
+                            ui.selectable_value(slot, Some(idx), name);
+                        }
+                    });
+            }
+            ui.checkbox(&mut self.compare_overlay, "Overlay");
+            if self.compare_overlay {
+                ui.add(egui::Slider::new(&mut self.compare_opacity, 0.0..=1.0).text("B opacity"));
+            }
+        });
+        let (a, b) = (self.compare_profile(self.compare_a), self.compare_profile(self.compare_b));
+        let background = [60, 60, 60, 255];
+        let (width, height) = preview::pixels_for(ui.ctx(), egui::vec2(160.0, 160.0), self.preview_zoom);
+        let mut buffer_a = RgbaBuffer::new(width, height, background);
+        raster::draw_crosshair(&mut buffer_a, &a, self.preview_resolution, 0.0);
+        if self.compare_overlay {
+            let mut buffer_b = RgbaBuffer::new(width, height, [0, 0, 0, 0]);
+            raster::draw_crosshair(&mut buffer_b, &b, self.preview_resolution, 0.0);
+            raster::composite(&mut buffer_a, &buffer_b, self.compare_opacity);
+            preview::show_buffer(ui, &mut self.compare_textures[0], "compare_overlay", &buffer_a, self.preview_zoom);
         } else {
-            for _ in 0..25 {
-                let remainder = (&num % &dict_base).to_u64_digits().get(0).copied().unwrap_or(0) as usize;
-                num /= &dict_base;
-                code.insert(0, DICTIONARY.chars().nth(remainder).unwrap_or('a'));
+            let mut buffer_b = RgbaBuffer::new(width, height, background);
+            raster::draw_crosshair(&mut buffer_b, &b, self.preview_resolution, 0.0);
+            ui.horizontal(|ui| {
+                let [texture_a, texture_b] = &mut self.compare_textures;
+                preview::show_buffer(ui, texture_a, "compare_a", &buffer_a, self.preview_zoom);
+                preview::show_buffer(ui, texture_b, "compare_b", &buffer_b, self.preview_zoom);
+            });
+        }
+
+        let diffs = compare::diff_profiles(&a, &b);
+        if diffs.is_empty() {
+            ui.label("Both profiles have identical settings.");
+            return;
+        }
+        egui::Grid::new("compare_diffs").striped(true).num_columns(4).show(ui, |ui| {
+            ui.strong("Setting");
+            ui.strong("A");
+            ui.strong("B");
+            ui.strong("Δ (B − A)");
+            ui.end_row();
+            for diff in &diffs {
+                ui.monospace(diff.name);
+                ui.label(diff.a.display());
+                ui.label(diff.b.display());
+                ui.label(diff.delta().map_or_else(String::new, |d| format!("{:+.2}", d)));
+                ui.end_row();
+            }
+        });
+    }
+
+    fn export_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Resolution:");
+            egui::ComboBox::from_id_source("export_resolution")
+                .selected_text(self.export_options.resolution.label())
+                .show_ui(ui, |ui| {
+                    for resolution in render::COMMON_RESOLUTIONS {
+                        ui.selectable_value(&mut self.export_options.resolution, resolution, resolution.label());
+                    }
+                });
+            ui.add(egui::Slider::new(&mut self.export_options.zoom, 1..=16).text("Zoom"));
+            ui.add(egui::Slider::new(&mut self.export_options.size, 16..=512).text("Area (px)"));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Background:");
+            let background = &mut self.export_options.background;
+            if ui.radio(*background == ExportBackground::Transparent, "Transparent").clicked() {
+                *background = ExportBackground::Transparent;
+            }
+            if ui.radio(matches!(background, ExportBackground::Solid(_)), "Solid").clicked() && !matches!(background, ExportBackground::Solid(_)) {
+                *background = ExportBackground::Solid([40, 40, 40, 255]);
+            }
+            if ui.radio(matches!(background, ExportBackground::Image(_)), "Image").clicked() {
+                *background = ExportBackground::Image(PathBuf::from(&self.export_bg_path));
             }
+            match background {
+                ExportBackground::Solid(rgba) => {
+                    ui.color_edit_button_srgba_unmultiplied(rgba);
+                }
+                ExportBackground::Image(path) => {
+                    if ui.add(egui::TextEdit::singleline(&mut self.export_bg_path).hint_text("path to PNG or JPEG")).changed() {
+                        *path = PathBuf::from(&self.export_bg_path);
+                    }
+                }
+                ExportBackground::Transparent => {}
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Output folder:");
+            ui.text_edit_singleline(&mut self.export_dir);
+        });
+        ui.horizontal(|ui| {
+            if ui.button("🖼 Export active profile").clicked() {
+                let profile = self.active_profile.clone();
+                self.export_png(&profile);
+            }
+            if ui.button("📁 Export whole library").clicked() {
+                match export::export_library(&self.crosshair_library, Path::new(&self.export_dir), &self.export_options) {
+                    Ok(written) => self.success_message = format!("Rendered {} crosshairs into {}", written.len(), self.export_dir),
+                    Err(e) => self.error_message = format!("PNG export failed: {}", e),
+                }
+            }
+        });
+    }
+
+    fn export_png(&mut self, profile: &CrosshairProfile) {
+        let dir = PathBuf::from(&self.export_dir);
+        let path = dir.join(format!("{}.png", export::file_stem(&profile.name)));
+        let result = fs::create_dir_all(&dir).map_err(|e| e.to_string())
+            .and_then(|_| export::export_profile(profile, &path, &self.export_options));
+        match result {
+            Ok(()) => self.success_message = format!("Exported {}", path.display()),
+            Err(e) => self.error_message = format!("PNG export failed: {}", e),
         }
+    }
+
+    fn bulk_import_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label("Paste a chat log or list with crosshair codes:");
+        ui.add(egui::TextEdit::multiline(&mut self.bulk_text).desired_rows(4).desired_width(f32::INFINITY));
+        ui.horizontal(|ui| {
+            if ui.button("🔍 Scan text").clicked() {
+                self.bulk_results = bulk_import::scan_text(&self.bulk_text, &self.crosshair_library);
+                if self.bulk_results.is_empty() { self.error_message = "No valid crosshair codes found".to_string(); }
+            }
+            ui.separator();
+            ui.label("File:");
+            ui.text_edit_singleline(&mut self.bulk_path);
+            if ui.button("🔍 Scan file").clicked() {
+                match fs::read_to_string(&self.bulk_path) {
+                    Ok(content) => {
+                        self.bulk_results = bulk_import::scan_text(&content, &self.crosshair_library);
+                        if self.bulk_results.is_empty() { self.error_message = "No valid crosshair codes found".to_string(); }
+                    }
+                    Err(e) => self.error_message = format!("Could not read {}: {}", self.bulk_path, e),
+                }
+            }
+        });
+        if self.bulk_results.is_empty() { return; }
+
+        egui::ScrollArea::vertical().id_source("bulk_results").max_height(200.0).show(ui, |ui| {
+            egui::Grid::new("bulk_results_grid").striped(true).num_columns(5).show(ui, |ui| {
+                ui.label("");
+                ui.strong("Name");
+                ui.strong("Code");
+                ui.strong("Color");
+                ui.strong("Status");
+                ui.end_row();
+                for found in &mut self.bulk_results {
+                    ui.checkbox(&mut found.selected, "");
+                    ui.text_edit_singleline(&mut found.name);
+                    ui.monospace(&found.code);
+                    let (rect, _) = ui.allocate_exact_size(egui::vec2(16.0, 16.0), egui::Sense::hover());
+                    ui.painter().rect_filled(rect, 2.0, egui::Color32::from_rgb(found.profile.red, found.profile.green, found.profile.blue));
+                    match (&found.duplicate_of, &found.similar_to) {
+                        (Some(name), _) => ui.colored_label(egui::Color32::YELLOW, format!("same as \"{}\"", name)),
+                        (None, Some(name)) => ui.colored_label(egui::Color32::LIGHT_BLUE, format!("new, close to \"{}\"", name)),
+                        (None, None) => ui.label("new"),
+                    };
+                    ui.end_row();
+                }
+            });
+        });
+        ui.horizontal(|ui| {
+            let selected = self.bulk_results.iter().filter(|f| f.selected).count();
+            if ui.add_enabled(selected > 0, egui::Button::new(format!("📥 Import selected ({})", selected))).clicked() {
+                self.remember(format!("Bulk import {} crosshairs", selected));
+                for found in self.bulk_results.drain(..).filter(|f| f.selected) {
+                    let mut profile = found.profile;
+                    profile.name = pack::unique_name(&self.crosshair_library, found.name.trim());
+                    self.crosshair_library.push(profile);
+                }
+                self.save_crosshair_profiles();
+                self.success_message = format!("Imported {} crosshairs", selected);
+            }
+            if ui.button("Clear").clicked() {
+                self.bulk_results.clear();
+            }
+        });
+    }
+
+    fn pack_ui(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("pack_meta").num_columns(2).show(ui, |ui| {
+            ui.label("Pack file:");
+            ui.text_edit_singleline(&mut self.pack_path);
+            ui.end_row();
+            ui.label("Author:");
+            ui.text_edit_singleline(&mut self.pack_author);
+            ui.end_row();
+            ui.label("Description:");
+            ui.text_edit_singleline(&mut self.pack_description);
+            ui.end_row();
+        });
+        ui.horizontal(|ui| {
+            let selected = self.pack_selection.len();
+            if ui.add_enabled(selected > 0, egui::Button::new(format!("📤 Export selected ({})", selected))).clicked() {
+                let profiles: Vec<CrosshairProfile> = self.pack_selection.iter().filter_map(|&idx| self.crosshair_library.get(idx).cloned()).collect();
+                match pack::export_pack(Path::new(&self.pack_path), &self.pack_author, &self.pack_description, profiles) {
+                    Ok(()) => self.success_message = format!("Exported {} crosshairs to {}", selected, self.pack_path),
+                    Err(e) => self.error_message = format!("Pack export failed: {}", e),
+                }
+            }
+            if ui.button("Select all shown").clicked() {
+                let shown = library::filtered_sorted(&self.crosshair_library, &self.library_search, self.library_favorites_only, self.library_sort);
+                self.pack_selection.extend(shown);
+            }
+            if ui.button("Clear selection").clicked() {
+                self.pack_selection.clear();
+            }
+            if ui.button("📥 Load pack").clicked() {
+                match pack::load_pack(Path::new(&self.pack_path)) {
+                    Ok(loaded) => self.pending_import = Some(pack::plan_import(&self.crosshair_library, loaded)),
+                    Err(e) => self.error_message = format!("Pack import failed: {}", e),
+                }
+            }
+        });
+
+        let mut finished = None;
+        if let Some(pending) = &mut self.pending_import {
+            ui.separator();
+            ui.label(format!("Pack by {} — {}", if pending.pack.author.is_empty() { "unknown" } else { &pending.pack.author }, pending.pack.description));
+            ui.small(format!("Created: {} (unix time), {} crosshairs", pending.pack.created, pending.pack.profiles.len()));
+            egui::Grid::new("pack_import_items").striped(true).num_columns(3).show(ui, |ui| {
+                for (i, item) in pending.items.iter_mut().enumerate() {
+                    ui.label(&item.profile.name);
+                    match &item.status {
+                        ImportStatus::New => match &item.similar_to {
+                            Some(name) => { ui.colored_label(egui::Color32::LIGHT_BLUE, format!("new, close to \"{}\"", name)); ui.label(""); }
+                            None => { ui.label("new"); ui.label(""); }
+                        },
+                        ImportStatus::Duplicate(existing) => { ui.label(format!("duplicate of \"{}\"", existing)); ui.label("skipped"); }
+                        ImportStatus::NameConflict => {
+                            ui.colored_label(egui::Color32::YELLOW, "name already used");
+                            egui::ComboBox::from_id_source(format!("pack_conflict_{}", i))
+                                .selected_text(item.resolution.label())
+                                .show_ui(ui, |ui| {
+                                    for resolution in ConflictResolution::ALL {
+                                        ui.selectable_value(&mut item.resolution, resolution, resolution.label());
+                                    }
+                                });
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button("✅ Import").clicked() { finished = Some(true); }
+                if ui.button("Cancel").clicked() { finished = Some(false); }
+            });
+        }
+        match finished {
+            Some(true) => {
+                if let Some(pending) = self.pending_import.take() {
+                    self.remember("Import pack");
+                    let summary = pack::apply_import(&mut self.crosshair_library, pending);
+                    self.save_crosshair_profiles();
+                    self.success_message = format!("Imported pack: {} added, {} replaced, {} skipped, {} duplicates ignored", summary.added, summary.replaced, summary.skipped, summary.duplicates);
+                }
+            }
+            Some(false) => self.pending_import = None,
+            None => {}
+        }
+    }
 
-        format!("CSGO-{}-{}-{}-{}-{}", &code[0..5], &code[5..10], &code[10..15], &code[15..20], &code[20..25])
+    fn library_row_ui(&mut self, ui: &mut egui::Ui, idx: usize, to_delete: &mut Option<usize>) {
+        let profile = self.crosshair_library[idx].clone();
+        ui.horizontal(|ui| {
+            let mut in_pack = self.pack_selection.contains(&idx);
+            if ui.checkbox(&mut in_pack, "").on_hover_text("Include in pack export").changed() {
+                if in_pack { self.pack_selection.insert(idx); } else { self.pack_selection.remove(&idx); }
+            }
+            if ui.selectable_label(profile.favorite, if profile.favorite { "⭐" } else { "☆" }).on_hover_text("Toggle favorite").clicked() {
+                self.remember(format!("Toggle favorite \"{}\"", profile.name));
+                self.crosshair_library[idx].favorite = !profile.favorite;
+                self.save_crosshair_profiles();
+            }
+            let label = format!("{} (R:{}, G:{}, B:{})", profile.name, profile.red, profile.green, profile.blue);
+            if ui.selectable_label(self.selected_library_idx == Some(idx), &label).clicked() {
+                self.select_library_entry(idx);
+            }
+            if ui.button("🖼").on_hover_text("Export PNG").clicked() {
+                self.export_png(&profile);
+            }
+            if ui.button("🖨 Copy Code").clicked() {
+                let code = self.generate_crosshair_code(&profile);
+                ui.output_mut(|o| o.copied_text = code);
+                self.success_message = "Crosshair code copied to clipboard!".to_string();
+            }
+            let mut name = profile.name.clone();
+            let rename_response = ui.text_edit_singleline(&mut name);
+            if rename_response.changed() {
+                let (active_profile, library) = (&self.active_profile, &self.crosshair_library);
+                self.history.record_continuous("Rename crosshair", Some(idx), || Snapshot { active_profile: active_profile.clone(), library: library.clone() });
+                self.crosshair_library[idx].name = name;
+                self.save_crosshair_profiles();
+            }
+            if ui.button("🗑 Delete").clicked() {
+                *to_delete = Some(idx);
+            }
+            for tag in &profile.tags {
+                ui.small(format!("#{}", tag));
+            }
+        });
     }
 }
 
 impl eframe::App for CS2ConfigApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
+        self.handle_history_shortcuts(ctx);
+        egui::CentralPanel::default().show(ctx, |ui| egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
             ui.heading("🎮 CS2 Config Manager");
             ui.separator();
 
@@ -542,7 +1945,7 @@ impl eframe::App for CS2ConfigApp {
                 }
                 AppState::Copying => {
                     if let Some(ref op) = self.copy_operation {
-                        ui.label(format!("Copying from {} to {}", op.from_id, op.to_id));
+                        ui.label(format!("Copying from {} to {}{}", op.from_id, op.to_id, if op.backup { " (with backup)" } else { "" }));
                         ui.add(egui::ProgressBar::new(op.progress).text(&op.status));
                     }
                     return;
@@ -605,7 +2008,7 @@ impl eframe::App for CS2ConfigApp {
             ui.separator();
 
             ui.horizontal(|ui| {
-                ui.checkbox(&mut self.create_backup, "Create backup of target config");
+                if self.show_backup_option { ui.checkbox(&mut self.create_backup, "Create backup of target config"); }
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     let can_copy = self.selected_source.is_some() && self.selected_target.is_some() && self.selected_source != self.selected_target && matches!(self.state, AppState::Ready);
                     if !can_copy {
@@ -631,6 +2034,9 @@ impl eframe::App for CS2ConfigApp {
                             for file in &account.config_files { ui.label(format!("  • {}", file)); }
                         }
                     });
+                    ui.collapsing("⚙ Settings Files (.vcfg)", |ui| self.settings_files_ui(ui, source_idx));
+                    ui.collapsing("⌨ Key Bindings", |ui| self.bindings_ui(ui, source_idx));
+                    ui.collapsing("🩺 Config Health", |ui| self.lint_ui(ui, source_idx));
                 }
             }
 
@@ -649,220 +2055,137 @@ impl eframe::App for CS2ConfigApp {
                             if profile.original_code.is_none() {
                                 profile.original_code = Some(code.clone());
                             }
+                            self.remember(format!("Import \"{}\"", profile.name));
                             self.crosshair_library.push(profile);
                             self.save_crosshair_profiles();
                             self.crosshair_code_input.clear();
-                        } else {
-                            self.error_message = "Invalid crosshair code".to_string();
                         }
                     }
                 });
 
+                ui.collapsing("📋 Bulk Import", |ui| self.bulk_import_ui(ui));
+
                 // Crosshair Library
                 ui.label("Crosshair Library:");
-                let profiles: Vec<(usize, CrosshairProfile)> = self.crosshair_library.iter().cloned().enumerate().collect();
-                egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
-                    let mut to_delete: Option<usize> = None;
-                    for (idx, profile) in profiles.iter() {
-                        ui.horizontal(|ui| {
-                            let label = format!("{} (R:{}, G:{}, B:{})", profile.name, profile.red, profile.green, profile.blue);
-                            if ui.selectable_label(self.selected_library_idx == Some(*idx), &label).clicked() {
-                                self.selected_library_idx = Some(*idx);
-                                self.active_profile = profile.clone();
-                            }
-                            if ui.button("🖨 Copy Code").clicked() {
-                                let code = self.generate_crosshair_code(profile);
-                                ui.output_mut(|o| o.copied_text = code);
-                                self.success_message = "Crosshair code copied to clipboard!".to_string();
-                            }
-                            let mut name = self.crosshair_library[*idx].name.clone();
-                            let rename_response = ui.text_edit_singleline(&mut name);
-                            if rename_response.changed() {
-                                self.crosshair_library[*idx].name = name;
-                                self.save_crosshair_profiles();
-                            }
-                            if ui.button("🗑 Delete").clicked() {
-                                to_delete = Some(*idx);
+                ui.horizontal(|ui| {
+                    ui.label("🔍");
+                    ui.add(egui::TextEdit::singleline(&mut self.library_search).hint_text("Search names, folders and tags"));
+                    ui.checkbox(&mut self.library_favorites_only, "⭐ Favorites only");
+                    egui::ComboBox::from_id_source("library_sort")
+                        .selected_text(format!("Sort: {}", self.library_sort.label()))
+                        .show_ui(ui, |ui| {
+                            for sort in LibrarySort::ALL {
+                                ui.selectable_value(&mut self.library_sort, sort, sort.label());
                             }
                         });
+                });
+                let indices = library::filtered_sorted(&self.crosshair_library, &self.library_search, self.library_favorites_only, self.library_sort);
+                let tree = library::build_tree(&self.crosshair_library, &indices);
+                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    let mut to_delete: Option<usize> = None;
+                    self.library_folder_ui(ui, "", &tree, &mut to_delete);
+                    if indices.is_empty() {
+                        ui.small("No crosshairs match the current filter.");
                     }
                     if let Some(idx) = to_delete {
+                        self.remember(format!("Delete \"{}\"", self.crosshair_library[idx].name));
                         self.crosshair_library.remove(idx);
                         self.save_crosshair_profiles();
-                        self.selected_library_idx = None;
+                        self.clear_library_selection();
                     }
                 });
 
+                if let Some(idx) = self.selected_library_idx.filter(|&idx| idx < self.crosshair_library.len()) {
+                    ui.horizontal(|ui| {
+                        ui.label("Folder:");
+                        ui.add(egui::TextEdit::singleline(&mut self.library_folder_input).desired_width(140.0).hint_text("e.g. Pros/NaVi"));
+                        egui::ComboBox::from_id_source("library_folder_pick")
+                            .selected_text("📁")
+                            .show_ui(ui, |ui| {
+                                for folder in library::all_folders(&self.crosshair_library) {
+                                    if ui.selectable_label(self.library_folder_input == folder, &folder).clicked() {
+                                        self.library_folder_input = folder;
+                                    }
+                                }
+                            });
+                        ui.label("Tags:");
+                        ui.add(egui::TextEdit::singleline(&mut self.library_tags_input).desired_width(160.0).hint_text("comma separated"));
+                        if ui.button("💾 Save").clicked() {
+                            self.remember("Edit folder and tags");
+                            let profile = &mut self.crosshair_library[idx];
+                            profile.folder = library::normalize_folder(&self.library_folder_input);
+                            profile.tags = library::parse_tags(&self.library_tags_input);
+                            self.library_folder_input = profile.folder.clone();
+                            self.library_tags_input = profile.tags.join(", ");
+                            self.save_crosshair_profiles();
+                        }
+                    });
+                }
+
+                ui.collapsing("📦 Crosshair Packs", |ui| self.pack_ui(ui));
+                ui.collapsing("🔎 Similar Crosshairs", |ui| self.similar_ui(ui));
+
                 if ui.button("➕ Add New Crosshair").clicked() {
-                    self.crosshair_library.push(self.active_profile.clone());
+                    let mut profile = self.active_profile.clone();
+                    profile.added_at = library::now_timestamp();
+                    self.remember(format!("Add \"{}\"", profile.name));
+                    self.crosshair_library.push(profile);
                     self.save_crosshair_profiles();
                 }
 
                 // Active Profile Editor
                 ui.separator();
-                ui.label("Active Profile Settings:");
-                ui.add(egui::Slider::new(&mut self.active_profile.gap, -12.8..=12.7).text("Gap"));
-                ui.add(egui::Slider::new(&mut self.active_profile.outline_thickness, 0.0..=3.0).text("Outline Thickness"));
-                ui.add(egui::Slider::new(&mut self.active_profile.red, 0..=255).text("Red"));
-                ui.add(egui::Slider::new(&mut self.active_profile.green, 0..=255).text("Green"));
-                ui.add(egui::Slider::new(&mut self.active_profile.blue, 0..=255).text("Blue"));
-                ui.add(egui::Slider::new(&mut self.active_profile.alpha, 0..=255).text("Alpha"));
-                ui.add(egui::Slider::new(&mut self.active_profile.dynamic_splitdist, 0..=127).text("Dynamic Split Dist"));
-                ui.checkbox(&mut self.active_profile.recoil, "Recoil");
-                ui.add(egui::Slider::new(&mut self.active_profile.fixed_gap, -12.8..=12.7).text("Fixed Gap"));
-                ui.add(egui::Slider::new(&mut self.active_profile.color, 0..=5).text("Color"));
-                ui.checkbox(&mut self.active_profile.draw_outline, "Draw Outline");
-                ui.add(egui::Slider::new(&mut self.active_profile.dynamic_splitalpha_innermod, 0.0..=1.0).text("Dynamic Split Alpha Inner"));
-                ui.add(egui::Slider::new(&mut self.active_profile.dynamic_splitalpha_outermod, 0.3..=1.0).text("Dynamic Split Alpha Outer"));
-                ui.add(egui::Slider::new(&mut self.active_profile.dynamic_maxdist_split_ratio, 0.0..=1.0).text("Max Dist Split Ratio"));
-                ui.add(egui::Slider::new(&mut self.active_profile.thickness, 0.0..=6.3).text("Thickness"));
-                ui.add(egui::Slider::new(&mut self.active_profile.style, 0..=5).text("Style"));
-                ui.checkbox(&mut self.active_profile.dot, "Dot");
-                ui.checkbox(&mut self.active_profile.gap_use_weapon_value, "Gap Use Weapon Value");
-                ui.checkbox(&mut self.active_profile.use_alpha, "Use Alpha");
-                ui.checkbox(&mut self.active_profile.t, "T-Style");
-                ui.add(egui::Slider::new(&mut self.active_profile.size, 0.0..=819.1).text("Size"));
-
-                // Crosshair Preview
-                ui.separator();
-                ui.label("Crosshair Preview:");
-                let painter = ui.painter();
-                let rect = ui.available_rect_before_wrap();
-                let center = rect.center();
-
-                // Scaling factor to match CS2's pixel-based rendering (assuming 1920x1080 as reference)
-                const SCALE_FACTOR: f32 = 2.0; // Maps cl_crosshairsize 1.0 to ~10 pixels
-                let size = self.active_profile.size * SCALE_FACTOR;
-                let thickness = (self.active_profile.thickness * SCALE_FACTOR).max(1.0); // Ensure minimum thickness for visibility
-                let gap = if self.active_profile.gap_use_weapon_value && self.active_profile.fixed_gap != 0.0 {
-                    self.active_profile.fixed_gap * SCALE_FACTOR
-                } else {
-                    self.active_profile.gap * SCALE_FACTOR
-                };
-                let color = egui::Color32::from_rgba_unmultiplied(
-                    self.active_profile.red,
-                    self.active_profile.green,
-                    self.active_profile.blue,
-                    if self.active_profile.use_alpha { self.active_profile.alpha } else { 255 },
-                );
-
-                // Adjust rendering based on crosshair style
-                match self.active_profile.style {
-                    // Classic Static (style 4) or similar
-                    2 | 3 | 4 | 5 => {
-                        if !self.active_profile.t {
-                            // Standard crosshair: four lines
-                            painter.line_segment(
-                                [center + egui::vec2(-size - gap, 0.0), center + egui::vec2(-gap, 0.0)],
-                                (thickness, color),
-                            );
-                            painter.line_segment(
-                                [center + egui::vec2(gap, 0.0), center + egui::vec2(size + gap, 0.0)],
-                                (thickness, color),
-                            );
-                            painter.line_segment(
-                                [center + egui::vec2(0.0, -size - gap), center + egui::vec2(0.0, -gap)],
-                                (thickness, color),
-                            );
-                            painter.line_segment(
-                                [center + egui::vec2(0.0, gap), center + egui::vec2(0.0, size + gap)],
-                                (thickness, color),
-                            );
-                        } else {
-                            // T-style: horizontal line and vertical line starting from gap
-                            painter.line_segment(
-                                [center + egui::vec2(-size, 0.0), center + egui::vec2(size, 0.0)],
-                                (thickness, color),
-                            );
-                            painter.line_segment(
-                                [center + egui::vec2(0.0, gap), center + egui::vec2(0.0, size + gap)],
-                                (thickness, color),
-                            );
-                        }
+                ui.horizontal(|ui| {
+                    ui.label("Active Profile Settings:");
+                    if ui.add_enabled(self.history.can_undo(), egui::Button::new("↶ Undo")).on_hover_text("Ctrl+Z").clicked() {
+                        self.undo();
                     }
-                    // Dot-only or other styles
-                    _ => {
-                        // For simplicity, render a dot if style doesn't support lines
-                        if self.active_profile.dot {
-                            let dot_size = thickness * 0.5;
-                            painter.circle_filled(center, dot_size, color);
-                        }
+                    if ui.add_enabled(self.history.can_redo(), egui::Button::new("↷ Redo")).on_hover_text("Ctrl+Y").clicked() {
+                        self.redo();
                     }
+                });
+                let before = self.active_profile.clone();
+                if editor::editor_ui(ui, &mut self.active_profile) {
+                    let library = &self.crosshair_library;
+                    self.history.record_continuous("Edit active profile", None, || Snapshot { active_profile: before, library: library.clone() });
                 }
+                ui.collapsing("🕘 Edit History", |ui| self.history_ui(ui));
+                ui.collapsing("📐 Pixel Analysis", |ui| self.analysis_ui(ui));
 
-                // Draw dot if enabled
-                if self.active_profile.dot {
-                    let dot_size = thickness * 0.5; // CS2 dot is typically half the thickness
-                    painter.circle_filled(center, dot_size, color);
-                }
-
-                // Draw outline if enabled
-                if self.active_profile.draw_outline {
-                    let outline_thickness = (self.active_profile.outline_thickness * SCALE_FACTOR).max(1.0);
-                    let outline_color = egui::Color32::from_rgba_unmultiplied(0, 0, 0, if self.active_profile.use_alpha { self.active_profile.alpha } else { 255 });
-                    let offset = thickness * 0.5 + outline_thickness * 0.5; // Tighten outline to hug lines
-
-                    match self.active_profile.style {
-                        2 | 3 | 4 | 5 => {
-                            if !self.active_profile.t {
-                                // Outline for standard crosshair
-                                painter.line_segment(
-                                    [center + egui::vec2(-size - gap - offset, 0.0), center + egui::vec2(-gap + offset, 0.0)],
-                                    (outline_thickness, outline_color),
-                                );
-                                painter.line_segment(
-                                    [center + egui::vec2(gap - offset, 0.0), center + egui::vec2(size + gap + offset, 0.0)],
-                                    (outline_thickness, outline_color),
-                                );
-                                painter.line_segment(
-                                    [center + egui::vec2(0.0, -size - gap - offset), center + egui::vec2(0.0, -gap + offset)],
-                                    (outline_thickness, outline_color),
-                                );
-                                painter.line_segment(
-                                    [center + egui::vec2(0.0, gap - offset), center + egui::vec2(0.0, size + gap + offset)],
-                                    (outline_thickness, outline_color),
-                                );
-                            } else {
-                                // Outline for T-style
-                                painter.line_segment(
-                                    [center + egui::vec2(-size - offset, 0.0), center + egui::vec2(size + offset, 0.0)],
-                                    (outline_thickness, outline_color),
-                                );
-                                painter.line_segment(
-                                    [center + egui::vec2(0.0, gap - offset), center + egui::vec2(0.0, size + gap + offset)],
-                                    (outline_thickness, outline_color),
-                                );
-                            }
-                        }
-                        _ => {}
-                    }
+                // Crosshair Preview
+                ui.separator();
+                self.preview_ui(ui);
 
-                    // Outline for dot
-                    if self.active_profile.dot {
-                        let dot_size = thickness * 0.5;
-                        painter.circle(center, dot_size + outline_thickness * 0.5, outline_color, (outline_thickness, outline_color));
-                    }
-                }
+                ui.collapsing("⚖ Compare Profiles", |ui| self.compare_ui(ui));
+                ui.collapsing("🖼 Export PNG", |ui| self.export_ui(ui));
 
-                if let Some(target_idx) = self.selected_target {
-                    if let Some(account) = self.accounts.get(target_idx) {
-                        if let Some(config_path) = &account.cs2_config_path {
-                            let config_file = config_path.join("config.cfg");
-                            if ui.button("Apply to Config").clicked() {
-                                self.apply_crosshair_to_config(&self.active_profile, &config_file);
-                                self.success_message = "Crosshair applied to config!".to_string();
-                            }
-                        }
-                    }
-                }
+                self.apply_ui(ui);
             });
 
+            ui.separator();
+            ui.heading("🔫 Viewmodel Profiles");
+            self.viewmodel_ui(ui);
+
+            ui.separator();
+            ui.heading("🖱 Sensitivity");
+            self.sensitivity_ui(ui);
+
+            ui.separator();
+            ui.heading("🧩 Settings Profiles");
+            for (idx, category) in CATEGORIES.iter().enumerate() {
+                ui.collapsing(category.title, |ui| self.convar_profile_ui(ui, idx));
+            }
+
             ui.small("💡 Tip: Make sure CS2 is closed before applying configurations.");
-        });
+        }));
     }
 }
 
 fn main() -> Result<(), eframe::Error> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([800.0, 600.0])
@@ -0,0 +1,225 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::share_code;
+use crate::similarity;
+use crate::CrosshairProfile;
+
+pub const PACK_FORMAT_VERSION: u32 = 1;
+
+/// A shareable set of crosshairs, written as a single JSON file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrosshairPack {
+    pub format_version: u32,
+    pub author: String,
+    pub description: String,
+    pub created: u64,
+    pub profiles: Vec<CrosshairProfile>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    KeepBoth,
+    Replace,
+    Skip,
+}
+
+impl ConflictResolution {
+    pub const ALL: [ConflictResolution; 3] = [ConflictResolution::KeepBoth, ConflictResolution::Replace, ConflictResolution::Skip];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ConflictResolution::KeepBoth => "Keep both (rename)",
+            ConflictResolution::Replace => "Replace existing",
+            ConflictResolution::Skip => "Skip",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportStatus {
+    New,
+    /// Same settings as an existing library entry, the profile is not imported again.
+    Duplicate(String),
+    /// Same name as an existing entry but different settings. The entry is looked up by
+    /// name again on import, the library may have changed since the plan was made.
+    NameConflict,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportItem {
+    pub profile: CrosshairProfile,
+    pub status: ImportStatus,
+    pub resolution: ConflictResolution,
+    /// Closest existing profile within the near-duplicate distance, for new profiles.
+    pub similar_to: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PendingImport {
+    pub pack: CrosshairPack,
+    pub items: Vec<ImportItem>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub replaced: usize,
+    pub skipped: usize,
+    pub duplicates: usize,
+}
+
+pub fn export_pack(path: &Path, author: &str, description: &str, profiles: Vec<CrosshairProfile>) -> Result<(), String> {
+    let pack = CrosshairPack {
+        format_version: PACK_FORMAT_VERSION,
+        author: author.to_string(),
+        description: description.to_string(),
+        created: crate::library::now_timestamp(),
+        profiles,
+    };
+    let content = serde_json::to_string_pretty(&pack).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+pub fn load_pack(path: &Path) -> Result<CrosshairPack, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let pack: CrosshairPack = serde_json::from_str(&content).map_err(|e| format!("Not a crosshair pack: {}", e))?;
+    if pack.format_version > PACK_FORMAT_VERSION {
+        return Err(format!("Pack format {} is newer than supported ({})", pack.format_version, PACK_FORMAT_VERSION));
+    }
+    Ok(pack)
+}
+
+/// Compares every pack profile against the library and against earlier profiles of the
+/// same pack. Duplicates are detected on the decoded settings, not on name or code.
+pub fn plan_import(library: &[CrosshairProfile], pack: CrosshairPack) -> PendingImport {
+    let mut items: Vec<ImportItem> = Vec::new();
+    for profile in &pack.profiles {
+        let duplicate = library.iter()
+            .chain(items.iter().map(|i| &i.profile))
+            .find(|existing| share_code::same_settings(existing, profile))
+            .map(|existing| existing.name.clone());
+        let status = if let Some(name) = duplicate {
+            ImportStatus::Duplicate(name)
+        } else if library.iter().any(|p| p.name == profile.name) {
+            ImportStatus::NameConflict
+        } else {
+            ImportStatus::New
+        };
+        let similar_to = if status == ImportStatus::New {
+            similarity::near_duplicate(library.iter().chain(items.iter().map(|i| &i.profile)), profile)
+        } else {
+            None
+        };
+        items.push(ImportItem { profile: profile.clone(), status, resolution: ConflictResolution::KeepBoth, similar_to });
+    }
+    PendingImport { pack, items }
+}
+
+/// Returns `name`, or `name (2)`, `name (3)`, ... if that name is already taken.
+pub fn unique_name(library: &[CrosshairProfile], name: &str) -> String {
+    if !library.iter().any(|p| p.name == name) {
+        return name.to_string();
+    }
+    (2..).map(|n| format!("{} ({})", name, n)).find(|candidate| !library.iter().any(|p| &p.name == candidate)).unwrap_or_default()
+}
+
+pub fn apply_import(library: &mut Vec<CrosshairProfile>, pending: PendingImport) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+    let now = crate::library::now_timestamp();
+    for item in pending.items {
+        let mut profile = item.profile;
+        profile.added_at = now;
+        match (item.status, item.resolution) {
+            (ImportStatus::Duplicate(_), _) => summary.duplicates += 1,
+            (ImportStatus::New, _) => {
+                profile.name = unique_name(library, &profile.name);
+                library.push(profile);
+                summary.added += 1;
+            }
+            (ImportStatus::NameConflict, ConflictResolution::Skip) => summary.skipped += 1,
+            (ImportStatus::NameConflict, ConflictResolution::Replace) => match library.iter().position(|p| p.name == profile.name) {
+                Some(idx) => {
+                    library[idx] = profile;
+                    summary.replaced += 1;
+                }
+                None => {
+                    library.push(profile);
+                    summary.added += 1;
+                }
+            },
+            (ImportStatus::NameConflict, ConflictResolution::KeepBoth) => {
+                profile.name = unique_name(library, &profile.name);
+                library.push(profile);
+                summary.added += 1;
+            }
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, gap: f32) -> CrosshairProfile {
+        let mut profile = CrosshairProfile { name: name.to_string(), ..share_code::sample_profile() };
+        profile.gap = gap;
+        profile
+    }
+
+    fn pack(profiles: Vec<CrosshairProfile>) -> CrosshairPack {
+        CrosshairPack { format_version: PACK_FORMAT_VERSION, author: String::new(), description: String::new(), created: 0, profiles }
+    }
+
+    #[test]
+    fn unique_name_appends_the_first_free_number() {
+        let library = vec![profile("a", 0.0), profile("a (2)", 1.0)];
+        assert_eq!(unique_name(&library, "b"), "b");
+        assert_eq!(unique_name(&library, "a"), "a (3)");
+    }
+
+    #[test]
+    fn plan_detects_duplicates_by_settings_and_conflicts_by_name() {
+        let library = vec![profile("existing", -1.0)];
+        let pending = plan_import(&library, pack(vec![
+            profile("renamed copy", -1.0),
+            profile("existing", 2.0),
+            profile("fresh", 3.0),
+            profile("fresh again", 3.0),
+        ]));
+        let statuses: Vec<&ImportStatus> = pending.items.iter().map(|i| &i.status).collect();
+        assert_eq!(statuses, vec![
+            &ImportStatus::Duplicate("existing".to_string()),
+            &ImportStatus::NameConflict,
+            &ImportStatus::New,
+            &ImportStatus::Duplicate("fresh".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn replace_finds_the_conflicting_entry_by_name_after_library_changes() {
+        let mut library = vec![profile("other", 0.0), profile("existing", -1.0)];
+        let mut pending = plan_import(&library, pack(vec![profile("existing", 2.0)]));
+        pending.items[0].resolution = ConflictResolution::Replace;
+        library.remove(0);
+        library.push(profile("later", 4.0));
+
+        let summary = apply_import(&mut library, pending);
+        assert_eq!(summary.replaced, 1);
+        let names: Vec<&str> = library.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["existing", "later"]);
+        assert_eq!(library[0].gap, 2.0);
+    }
+
+    #[test]
+    fn keep_both_renames_the_imported_profile() {
+        let mut library = vec![profile("existing", -1.0)];
+        let pending = plan_import(&library, pack(vec![profile("existing", 2.0)]));
+        let summary = apply_import(&mut library, pending);
+        assert_eq!(summary.added, 1);
+        assert_eq!(library[1].name, "existing (2)");
+    }
+}
@@ -0,0 +1,54 @@
+use eframe::egui;
+
+use crate::raster::RgbaBuffer;
+
+pub fn to_color_image(buffer: &RgbaBuffer) -> egui::ColorImage {
+    egui::ColorImage::from_rgba_unmultiplied([buffer.width as usize, buffer.height as usize], &buffer.pixels)
+}
+
+/// Uploads `buffer` into the texture kept in `slot` and shows it with every buffer pixel
+/// covering `zoom` physical pixels. Nearest filtering keeps pixel edges hard.
+pub fn show_buffer(ui: &mut egui::Ui, slot: &mut Option<egui::TextureHandle>, name: &str, buffer: &RgbaBuffer, zoom: f32) -> egui::Response {
+    let image = to_color_image(buffer);
+    let texture = match slot {
+        Some(texture) => {
+            texture.set(image, egui::TextureOptions::NEAREST);
+            texture
+        }
+        None => slot.insert(ui.ctx().load_texture(name, image, egui::TextureOptions::NEAREST)),
+    };
+    let size = egui::vec2(buffer.width as f32, buffer.height as f32) * zoom / ui.ctx().pixels_per_point();
+    ui.add(egui::Image::new((texture.id(), size)).sense(egui::Sense::click_and_drag()))
+}
+
+/// Number of buffer pixels that fit into `points` when each buffer pixel covers `zoom` physical pixels.
+pub fn pixels_for(ctx: &egui::Context, points: egui::Vec2, zoom: f32) -> (u32, u32) {
+    let pixels = points * ctx.pixels_per_point() / zoom;
+    (pixels.x.max(1.0) as u32, pixels.y.max(1.0) as u32)
+}
+
+/// Draws grid lines over a buffer shown at `rect`: one line every `spacing` buffer pixels
+/// per axis, aligned so a line passes through the buffer center. Skipped while the cells
+/// are too small to see.
+pub fn draw_pixel_grid(ui: &egui::Ui, rect: egui::Rect, buffer_size: [u32; 2], spacing: [f32; 2], color: egui::Color32) {
+    let points_per_pixel = rect.width() / buffer_size[0].max(1) as f32;
+    if spacing[0] * points_per_pixel < 4.0 || spacing[1] * points_per_pixel < 4.0 { return; }
+    let painter = ui.painter_at(rect);
+    let stroke = egui::Stroke::new(1.0, color);
+    let center = [(buffer_size[0] / 2) as f32, (buffer_size[1] / 2) as f32];
+    for axis in 0..2 {
+        let first = center[axis] - (center[axis] / spacing[axis]).floor() * spacing[axis];
+        let mut line = first;
+        while line <= buffer_size[axis] as f32 {
+            let offset = line * points_per_pixel;
+            if axis == 0 {
+                let x = rect.left() + offset;
+                painter.line_segment([egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())], stroke);
+            } else {
+                let y = rect.top() + offset;
+                painter.line_segment([egui::pos2(rect.left(), y), egui::pos2(rect.right(), y)], stroke);
+            }
+            line += spacing[axis];
+        }
+    }
+}
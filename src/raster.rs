@@ -0,0 +1,262 @@
+use crate::render::{self, Blend, Primitive, Resolution};
+use crate::CrosshairProfile;
+
+/// Straight (non premultiplied) RGBA8 pixel buffer, row major.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbaBuffer {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbaBuffer {
+    pub fn new(width: u32, height: u32, fill: [u8; 4]) -> Self {
+        let pixels = fill.iter().copied().cycle().take(width as usize * height as usize * 4).collect();
+        Self { width, height, pixels }
+    }
+
+    fn blend(&mut self, x: u32, y: u32, rgba: [u8; 4], coverage: f32, blend: Blend) {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        let dst = &mut self.pixels[i..i + 4];
+        let src_a = rgba[3] as f32 / 255.0 * coverage;
+        let dst_a = dst[3] as f32 / 255.0;
+        match blend {
+            Blend::Alpha => {
+                let out_a = src_a + dst_a * (1.0 - src_a);
+                if out_a <= 0.0 { return; }
+                for c in 0..3 {
+                    let value = (rgba[c] as f32 * src_a + dst[c] as f32 * dst_a * (1.0 - src_a)) / out_a;
+                    dst[c] = value.round().clamp(0.0, 255.0) as u8;
+                }
+                dst[3] = (out_a * 255.0).round() as u8;
+            }
+            Blend::Additive => {
+                for c in 0..3 {
+                    let value = dst[c] as f32 * dst_a + rgba[c] as f32 * src_a;
+                    let out_a = (dst_a + src_a).min(1.0);
+                    dst[c] = if out_a > 0.0 { (value / out_a).round().clamp(0.0, 255.0) as u8 } else { 0 };
+                }
+                dst[3] = ((dst_a + src_a).min(1.0) * 255.0).round() as u8;
+            }
+        }
+    }
+}
+
+/// Fills every primitive in order. Pixels partially covered by fractional edges get a
+/// proportional share of the primitive's alpha.
+pub fn rasterize(buffer: &mut RgbaBuffer, primitives: &[Primitive]) {
+    for p in primitives {
+        let x_start = p.x0.floor().max(0.0) as u32;
+        let y_start = p.y0.floor().max(0.0) as u32;
+        let x_end = (p.x1.ceil().max(0.0) as u32).min(buffer.width);
+        let y_end = (p.y1.ceil().max(0.0) as u32).min(buffer.height);
+        for y in y_start..y_end {
+            let cover_y = (p.y1.min(y as f32 + 1.0) - p.y0.max(y as f32)).clamp(0.0, 1.0);
+            for x in x_start..x_end {
+                let cover_x = (p.x1.min(x as f32 + 1.0) - p.x0.max(x as f32)).clamp(0.0, 1.0);
+                let coverage = cover_x * cover_y;
+                if coverage > 0.0 {
+                    buffer.blend(x, y, p.rgba, coverage, p.blend);
+                }
+            }
+        }
+    }
+}
+
+/// Blends `src` over `dst` (same size) with its alpha scaled by `opacity`.
+pub fn composite(dst: &mut RgbaBuffer, src: &RgbaBuffer, opacity: f32) {
+    for y in 0..dst.height.min(src.height) {
+        for x in 0..dst.width.min(src.width) {
+            let i = (y as usize * src.width as usize + x as usize) * 4;
+            let rgba = [src.pixels[i], src.pixels[i + 1], src.pixels[i + 2], src.pixels[i + 3]];
+            if rgba[3] > 0 {
+                dst.blend(x, y, rgba, opacity, Blend::Alpha);
+            }
+        }
+    }
+}
+
+/// Draws the crosshair as it appears at `resolution`, with the screen center in the
+/// middle of `buffer`.
+pub fn draw_crosshair(buffer: &mut RgbaBuffer, profile: &CrosshairProfile, resolution: Resolution, spread: f32) {
+    let (cx, cy) = ((buffer.width / 2) as i32, (buffer.height / 2) as i32);
+    rasterize(buffer, &render::build_primitives(profile, resolution, cx, cy, spread));
+}
+
+/// Draws the crosshair as a monitor shows it when the game runs at `resolution` and every
+/// game pixel covers `scale` monitor pixels. Scaled output is filtered bilinearly like the
+/// GPU scaler, which is where the blur of stretched resolutions comes from.
+pub fn draw_crosshair_scaled(buffer: &mut RgbaBuffer, profile: &CrosshairProfile, resolution: Resolution, scale: [f32; 2], spread: f32) {
+    if scale == [1.0, 1.0] {
+        draw_crosshair(buffer, profile, resolution, spread);
+        return;
+    }
+    let game_width = (buffer.width as f32 / scale[0]).ceil() as u32 + 2;
+    let game_height = (buffer.height as f32 / scale[1]).ceil() as u32 + 2;
+    let mut game = RgbaBuffer::new(game_width, game_height, [0, 0, 0, 0]);
+    draw_crosshair(&mut game, profile, resolution, spread);
+    draw_scaled(buffer, &game, scale);
+}
+
+/// Blends `src`, magnified by `scale` around its center, over the center of `dst` using
+/// bilinear filtering on premultiplied colors.
+pub fn draw_scaled(dst: &mut RgbaBuffer, src: &RgbaBuffer, scale: [f32; 2]) {
+    let (dst_cx, dst_cy) = ((dst.width / 2) as f32, (dst.height / 2) as f32);
+    let (src_cx, src_cy) = ((src.width / 2) as f32, (src.height / 2) as f32);
+    let texel = |x: i64, y: i64| -> [f32; 4] {
+        if x < 0 || y < 0 || x >= src.width as i64 || y >= src.height as i64 {
+            return [0.0; 4];
+        }
+        let i = (y as usize * src.width as usize + x as usize) * 4;
+        let a = src.pixels[i + 3] as f32 / 255.0;
+        [src.pixels[i] as f32 * a, src.pixels[i + 1] as f32 * a, src.pixels[i + 2] as f32 * a, a]
+    };
+    for y in 0..dst.height {
+        let sy = (y as f32 + 0.5 - dst_cy) / scale[1] + src_cy - 0.5;
+        let (y0, fy) = (sy.floor(), sy - sy.floor());
+        for x in 0..dst.width {
+            let sx = (x as f32 + 0.5 - dst_cx) / scale[0] + src_cx - 0.5;
+            let (x0, fx) = (sx.floor(), sx - sx.floor());
+            let mut sum = [0.0f32; 4];
+            for (dx, dy, weight) in [(0, 0, (1.0 - fx) * (1.0 - fy)), (1, 0, fx * (1.0 - fy)), (0, 1, (1.0 - fx) * fy), (1, 1, fx * fy)] {
+                let t = texel(x0 as i64 + dx, y0 as i64 + dy);
+                for c in 0..4 { sum[c] += t[c] * weight; }
+            }
+            if sum[3] <= 1.0 / 512.0 { continue; }
+            let rgba = [(sum[0] / sum[3]).round() as u8, (sum[1] / sum[3]).round() as u8, (sum[2] / sum[3]).round() as u8, 255];
+            dst.blend(x, y, rgba, sum[3].min(1.0), Blend::Alpha);
+        }
+    }
+}
+
+/// Fills `buffer` with `background`, magnified by `zoom` around the image pixel `pan`
+/// (relative to the image center), using nearest sampling. Areas outside stay untouched.
+pub fn draw_background(buffer: &mut RgbaBuffer, background: &RgbaBuffer, pan: [f32; 2], zoom: f32) {
+    let zoom = zoom.max(0.01);
+    let (half_w, half_h) = (buffer.width as f32 / 2.0, buffer.height as f32 / 2.0);
+    let (bg_cx, bg_cy) = (background.width as f32 / 2.0 + pan[0], background.height as f32 / 2.0 + pan[1]);
+    for y in 0..buffer.height {
+        let sy = (bg_cy + (y as f32 + 0.5 - half_h) / zoom).floor();
+        if sy < 0.0 || sy >= background.height as f32 { continue; }
+        for x in 0..buffer.width {
+            let sx = (bg_cx + (x as f32 + 0.5 - half_w) / zoom).floor();
+            if sx < 0.0 || sx >= background.width as f32 { continue; }
+            let si = (sy as usize * background.width as usize + sx as usize) * 4;
+            let ti = (y as usize * buffer.width as usize + x as usize) * 4;
+            buffer.pixels[ti..ti + 4].copy_from_slice(&background.pixels[si..si + 4]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{export, share_code};
+    use std::path::PathBuf;
+
+    const SIZE: u32 = 64;
+    const BACKGROUND: [u8; 4] = [40, 40, 40, 255];
+
+    fn golden_path(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata").join("golden").join(format!("{}.png", name))
+    }
+
+    fn render_profile(profile: &CrosshairProfile, resolution: Resolution, spread: f32) -> RgbaBuffer {
+        let mut buffer = RgbaBuffer::new(SIZE, SIZE, BACKGROUND);
+        draw_crosshair(&mut buffer, profile, resolution, spread);
+        buffer
+    }
+
+    /// Compares against `testdata/golden/<name>.png`. Run with `UPDATE_GOLDEN=1` to regenerate.
+    fn assert_golden(name: &str, profile: &CrosshairProfile, resolution: Resolution) {
+        assert_golden_with_spread(name, profile, resolution, 0.0);
+    }
+
+    fn assert_golden_with_spread(name: &str, profile: &CrosshairProfile, resolution: Resolution, spread: f32) {
+        let rendered = render_profile(profile, resolution, spread);
+        let path = golden_path(name);
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            export::write_png(&path, &rendered).unwrap();
+            return;
+        }
+        let expected = export::load_image(&path).unwrap_or_else(|e| panic!("missing golden image: {}", e));
+        assert_eq!((rendered.width, rendered.height), (expected.width, expected.height), "{}: size differs", name);
+        let max_diff = rendered.pixels.iter().zip(&expected.pixels).map(|(a, b)| a.abs_diff(*b)).max().unwrap_or(0);
+        assert!(max_diff <= 1, "{}: rendered image differs from {} (max channel diff {})", name, path.display(), max_diff);
+    }
+
+    #[test]
+    fn fallen_dot_with_outline_1080p() {
+        assert_golden("fallen_1080p", &share_code::profile_from_code("CSGO-TpORA-p9Ley-TLQ3P-HzXJY-U9z6A"), Resolution::new(1920, 1080));
+    }
+
+    #[test]
+    fn zywoo_tight_cross_1080p() {
+        assert_golden("zywoo_1080p", &share_code::profile_from_code("CSGO-H3Wb2-YV2FB-VPipW-dx2td-hej5P"), Resolution::new(1920, 1080));
+    }
+
+    #[test]
+    fn device_outlined_cross_1280x960() {
+        assert_golden("device_1280x960", &share_code::sample_profile(), Resolution::new(1280, 960));
+    }
+
+    #[test]
+    fn t_style_additive_1440p() {
+        let mut profile = share_code::sample_profile();
+        profile.t = true;
+        profile.dot = true;
+        profile.use_alpha = false;
+        profile.draw_outline = false;
+        assert_golden("t_style_additive_1440p", &profile, Resolution::new(2560, 1440));
+    }
+
+    /// One golden per style, drawn right after a shot so every dynamic style is spread out.
+    /// The gaps differ so default styles show their fixed gap.
+    #[test]
+    fn every_style_while_firing_1080p() {
+        let mut profile = share_code::sample_profile();
+        profile.gap = -2.0;
+        profile.fixed_gap = 3.0;
+        profile.gap_use_weapon_value = false;
+        for style in 0..=5 {
+            profile.style = style;
+            let spread = render::SpreadState::Firing.spread(style, 0.0);
+            assert_golden_with_spread(&format!("style_{}_firing_1080p", style), &profile, Resolution::new(1920, 1080), spread);
+        }
+    }
+
+    #[test]
+    fn legacy_style_ignores_movement() {
+        for (style, moves) in [(0, true), (1, false), (2, true), (3, true), (4, false), (5, false)] {
+            assert_eq!(render::SpreadState::Walking.spread(style, 0.0) > 0.0, moves, "style {}", style);
+        }
+        assert!(render::SpreadState::Firing.spread(5, 0.0) > 0.0);
+    }
+
+    #[test]
+    fn arms_are_symmetric_around_center() {
+        let profile = share_code::sample_profile();
+        let m = render::metrics(&profile, Resolution::new(1920, 1080));
+        let segments = render::crosshair_segments(&profile, &m, 100, 100, 0);
+        let (left, right) = (segments[0].rect, segments[1].rect);
+        let bar_left = 100 - m.bar_thickness / 2;
+        let bar_right = bar_left + m.bar_thickness;
+        assert_eq!(left[2] - left[0], right[2] - right[0]);
+        assert_eq!(bar_left - left[2], right[0] - bar_right);
+    }
+
+    #[test]
+    fn stretched_4_3_widens_the_crosshair() {
+        let profile = share_code::sample_profile();
+        let game = Resolution::new(1280, 960);
+        let scale = render::AspectMode::Stretched.scale(game, Resolution::new(1920, 1080));
+        let mut buffer = RgbaBuffer::new(SIZE, SIZE, [0, 0, 0, 0]);
+        draw_crosshair_scaled(&mut buffer, &profile, game, scale, 0.0);
+        let extent = |horizontal: bool| (0..SIZE).filter(|&i| {
+            let (x, y) = if horizontal { (i, SIZE / 2) } else { (SIZE / 2, i) };
+            buffer.pixels[((y * SIZE + x) * 4 + 3) as usize] > 0
+        }).count();
+        assert!(extent(true) > extent(false), "horizontal {} vs vertical {}", extent(true), extent(false));
+    }
+}
@@ -0,0 +1,369 @@
+use crate::CrosshairProfile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Resolution {
+    pub const fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+
+    pub fn label(self) -> String {
+        format!("{}x{}", self.width, self.height)
+    }
+
+    /// Parses `1920x1080`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (width, height) = value.split_once(['x', 'X'])?;
+        let (width, height) = (width.trim().parse().ok()?, height.trim().parse().ok()?);
+        (width > 0 && height > 0).then_some(Self { width, height })
+    }
+}
+
+pub const COMMON_RESOLUTIONS: [Resolution; 7] = [
+    Resolution::new(1920, 1080),
+    Resolution::new(2560, 1440),
+    Resolution::new(3840, 2160),
+    Resolution::new(1280, 720),
+    Resolution::new(1280, 960),
+    Resolution::new(1024, 768),
+    Resolution::new(1440, 1080),
+];
+
+/// How a game resolution that differs from the monitor's is shown on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AspectMode {
+    /// One game pixel per monitor pixel, no scaling.
+    Native,
+    /// Scaled up evenly until it fills the monitor height, keeping its aspect ratio.
+    BlackBars,
+    /// Scaled to fill the whole monitor, so 4:3 becomes wider on 16:9.
+    Stretched,
+}
+
+impl AspectMode {
+    pub const ALL: [AspectMode; 3] = [AspectMode::Native, AspectMode::BlackBars, AspectMode::Stretched];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AspectMode::Native => "Native",
+            AspectMode::BlackBars => "Black bars",
+            AspectMode::Stretched => "Stretched",
+        }
+    }
+
+    /// Monitor pixels per game pixel, horizontally and vertically.
+    pub fn scale(self, game: Resolution, monitor: Resolution) -> [f32; 2] {
+        let sx = monitor.width as f32 / game.width as f32;
+        let sy = monitor.height as f32 / game.height as f32;
+        match self {
+            AspectMode::Native => [1.0, 1.0],
+            AspectMode::BlackBars => [sx.min(sy); 2],
+            AspectMode::Stretched => [sx, sy],
+        }
+    }
+}
+
+/// Crosshairs are authored against a 480 pixel high virtual screen (`YRES` in the game).
+const VIRTUAL_HEIGHT: f32 = 480.0;
+/// The gap is measured in 1080p pixels on top of a fixed four pixel base distance.
+const GAP_REFERENCE_HEIGHT: f32 = 1080.0;
+const GAP_BASE: f32 = 4.0;
+/// Alpha the game uses when `cl_crosshairusealpha 0` switches to additive drawing.
+const ADDITIVE_ALPHA: u8 = 200;
+
+/// Float to int conversion as done by the game, which rounds half to even.
+pub fn round_to_int(value: f32) -> i32 {
+    value.round_ties_even() as i32
+}
+
+pub fn yres(value: f32, resolution: Resolution) -> f32 {
+    value * resolution.height as f32 / VIRTUAL_HEIGHT
+}
+
+/// Resolves `cl_crosshaircolor` presets; only color 5 uses the custom RGB values.
+pub fn crosshair_rgb(profile: &CrosshairProfile) -> [u8; 3] {
+    match profile.color {
+        0 => [250, 50, 50],
+        1 => [50, 250, 50],
+        2 => [250, 250, 50],
+        3 => [50, 50, 250],
+        4 => [50, 250, 250],
+        _ => [profile.red, profile.green, profile.blue],
+    }
+}
+
+/// Pixel dimensions of a crosshair at one resolution, before it is split into rectangles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrosshairMetrics {
+    pub bar_size: i32,
+    pub bar_thickness: i32,
+    pub distance: i32,
+    pub split_distance: i32,
+    pub outline: Option<f32>,
+    pub rgb: [u8; 3],
+    pub alpha: u8,
+    pub additive: bool,
+}
+
+/// Whether the crosshair is drawn with `cl_fixedcrosshairgap` instead of `cl_crosshairgap`,
+/// which is the case with "use weapon gap" and on the default styles.
+pub fn uses_fixed_gap(profile: &CrosshairProfile) -> bool {
+    profile.gap_use_weapon_value || is_default_style(profile.style)
+}
+
+/// The gap value the crosshair is actually drawn with.
+pub fn effective_gap(profile: &CrosshairProfile) -> f32 {
+    if uses_fixed_gap(profile) { profile.fixed_gap } else { profile.gap }
+}
+
+/// Distance of the arms from the center bar in game pixels before rounding.
+pub fn gap_pixels(gap: f32, resolution: Resolution) -> f32 {
+    (GAP_BASE + gap) * resolution.height as f32 / GAP_REFERENCE_HEIGHT
+}
+
+pub fn metrics(profile: &CrosshairProfile, resolution: Resolution) -> CrosshairMetrics {
+    CrosshairMetrics {
+        bar_size: round_to_int(yres(profile.size, resolution)).max(0),
+        bar_thickness: round_to_int(yres(profile.thickness, resolution)).max(1),
+        distance: round_to_int(gap_pixels(effective_gap(profile), resolution)),
+        split_distance: round_to_int(yres(profile.dynamic_splitdist as f32, resolution)),
+        outline: profile.draw_outline.then_some(profile.outline_thickness),
+        rgb: crosshair_rgb(profile),
+        alpha: if profile.use_alpha { profile.alpha } else { ADDITIVE_ALPHA },
+        additive: !profile.use_alpha,
+    }
+}
+
+/// Screen rectangle `[x0, y0, x1, y1]` in game pixels, right and bottom edges exclusive.
+pub type PixelRect = [i32; 4];
+
+/// Styles 0 (default) and 1 (default static) ignore `cl_crosshairgap` and keep the
+/// fixed gap of `cl_fixedcrosshairgap`.
+pub fn is_default_style(style: u8) -> bool {
+    matches!(style, 0 | 1)
+}
+
+/// Styles 0 (default) and 2 (classic) split each arm into an inner and an outer part.
+pub fn is_split_style(style: u8) -> bool {
+    matches!(style, 0 | 2)
+}
+
+/// Styles 1 (default static) and 4 (classic static) ignore movement and firing.
+pub fn is_dynamic_style(style: u8) -> bool {
+    !matches!(style, 1 | 4)
+}
+
+/// Movement states simulated by the animated preview.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpreadState {
+    Standing,
+    Walking,
+    Running,
+    Firing,
+}
+
+impl SpreadState {
+    pub const ALL: [SpreadState; 4] = [SpreadState::Standing, SpreadState::Walking, SpreadState::Running, SpreadState::Firing];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SpreadState::Standing => "Standing",
+            SpreadState::Walking => "Walking",
+            SpreadState::Running => "Running",
+            SpreadState::Firing => "Firing",
+        }
+    }
+
+    /// Spread in virtual (480p) units for a crosshair `style`. Firing peaks right after
+    /// each shot and then recovers. Static styles never move and style 5 (classic legacy)
+    /// only reacts to firing.
+    pub fn spread(self, style: u8, time: f64) -> f32 {
+        const FIRE_RATE: f64 = 10.0;
+        if !is_dynamic_style(style) || (style == 5 && self != SpreadState::Firing) {
+            return 0.0;
+        }
+        match self {
+            SpreadState::Standing => 0.0,
+            SpreadState::Walking => 4.0,
+            SpreadState::Running => 10.0,
+            SpreadState::Firing => 7.0 * (1.0 - (time * FIRE_RATE).fract() as f32),
+        }
+    }
+}
+
+/// One rectangle of the crosshair, named after the part of the crosshair it draws.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub part: &'static str,
+    pub rect: PixelRect,
+    /// Alpha multiplier, the split alpha on split styles and 1 otherwise.
+    pub alpha: f32,
+}
+
+const ARMS: [&str; 4] = ["left", "right", "top", "bottom"];
+const INNER_ARMS: [&str; 4] = ["inner left", "inner right", "inner top", "inner bottom"];
+const OUTER_ARMS: [&str; 4] = ["outer left", "outer right", "outer top", "outer bottom"];
+
+/// Four arms of `len` pixels starting `start` pixels away from the center bar, in the
+/// game's order: left, right, top, bottom.
+fn arm_rects(m: &CrosshairMetrics, cx: i32, cy: i32, start: i32, len: i32) -> [PixelRect; 4] {
+    let half = m.bar_thickness / 2;
+    let (x0, y0) = (cx - half, cy - half);
+    let (x1, y1) = (x0 + m.bar_thickness, y0 + m.bar_thickness);
+    let inner_left = x0 - start;
+    let inner_right = x1 + start;
+    let inner_top = y0 - start;
+    let inner_bottom = y1 + start;
+    [
+        [inner_left - len, y0, inner_left, y1],
+        [inner_right, y0, inner_right + len, y1],
+        [x0, inner_top - len, x1, inner_top],
+        [x0, inner_bottom, x1, inner_bottom + len],
+    ]
+}
+
+/// The crosshair rectangles in drawing order for a screen center at `(cx, cy)`. This
+/// mirrors the game: arms are laid out around a center bar of `bar_thickness`, so odd and
+/// even thicknesses both stay symmetric. Arms are pushed out by `spread` pixels on dynamic
+/// styles; split styles move the inner part with the spread while the outer part follows
+/// only up to `cl_crosshair_dynamic_splitdist`. Parts without any length are left out.
+pub fn crosshair_segments(profile: &CrosshairProfile, m: &CrosshairMetrics, cx: i32, cy: i32, spread: i32) -> Vec<Segment> {
+    let spread = if is_dynamic_style(profile.style) { spread.max(0) } else { 0 };
+    let mut segments = Vec::with_capacity(9);
+    // T-style drops the top arm
+    let mut arms = |start: i32, len: i32, names: [&'static str; 4], alpha: f32| {
+        if len <= 0 { return; }
+        for (idx, rect) in arm_rects(m, cx, cy, start, len).into_iter().enumerate() {
+            if !(profile.t && idx == 2) {
+                segments.push(Segment { part: names[idx], rect, alpha });
+            }
+        }
+    };
+    if is_split_style(profile.style) {
+        let outer_len = round_to_int(m.bar_size as f32 * profile.dynamic_maxdist_split_ratio).clamp(0, m.bar_size);
+        let inner_len = m.bar_size - outer_len;
+        let outer_shift = spread.min(m.split_distance);
+        arms(m.distance + spread, inner_len, INNER_ARMS, profile.dynamic_splitalpha_innermod);
+        arms(m.distance + inner_len + outer_shift, outer_len, OUTER_ARMS, profile.dynamic_splitalpha_outermod);
+    } else {
+        arms(m.distance + spread, m.bar_size, ARMS, 1.0);
+    }
+    if profile.dot {
+        let half = m.bar_thickness / 2;
+        let rect = [cx - half, cy - half, cx - half + m.bar_thickness, cy - half + m.bar_thickness];
+        segments.push(Segment { part: "dot", rect, alpha: 1.0 });
+    }
+    segments
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Blend {
+    Alpha,
+    Additive,
+}
+
+/// A filled, axis aligned rectangle in game pixels. Edges may be fractional
+/// (outlines use the raw `cl_crosshair_outlinethickness`), right and bottom are exclusive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Primitive {
+    pub x0: f32,
+    pub y0: f32,
+    pub x1: f32,
+    pub y1: f32,
+    pub rgba: [u8; 4],
+    pub blend: Blend,
+}
+
+/// Computes everything needed to draw the crosshair, centered at `(cx, cy)` and spread out
+/// by `spread` virtual (480p) units, as a list of rectangles in painting order. Every
+/// backend (egui, CPU rasterizer) only consumes this list.
+pub fn build_primitives(profile: &CrosshairProfile, resolution: Resolution, cx: i32, cy: i32, spread: f32) -> Vec<Primitive> {
+    let m = metrics(profile, resolution);
+    let blend = if m.additive { Blend::Additive } else { Blend::Alpha };
+    let mut primitives = Vec::new();
+    for Segment { rect: [x0, y0, x1, y1], alpha: alpha_mod, .. } in crosshair_segments(profile, &m, cx, cy, round_to_int(yres(spread, resolution))) {
+        let (x0, y0, x1, y1) = (x0 as f32, y0 as f32, x1 as f32, y1 as f32);
+        let alpha = (m.alpha as f32 * alpha_mod.clamp(0.0, 1.0)).round() as u8;
+        if let Some(outline) = m.outline {
+            primitives.push(Primitive { x0: x0 - outline, y0: y0 - outline, x1: x1 + outline, y1: y1 + outline, rgba: [0, 0, 0, alpha], blend: Blend::Alpha });
+        }
+        primitives.push(Primitive { x0, y0, x1, y1, rgba: [m.rgb[0], m.rgb[1], m.rgb[2], alpha], blend });
+    }
+    primitives
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::share_code;
+
+    /// A plain cross at 480p, where virtual units are game pixels.
+    fn split_profile(style: u8) -> CrosshairProfile {
+        let mut profile = share_code::sample_profile();
+        profile.style = style;
+        (profile.size, profile.thickness, profile.gap, profile.t, profile.dot) = (4.0, 1.0, 0.0, false, false);
+        profile.gap_use_weapon_value = false;
+        profile.dynamic_splitdist = 3;
+        profile.dynamic_maxdist_split_ratio = 0.5;
+        profile.dynamic_splitalpha_innermod = 1.0;
+        profile.dynamic_splitalpha_outermod = 0.3;
+        profile
+    }
+
+    #[test]
+    fn split_style_moves_inner_part_with_spread_and_outer_part_up_to_split_distance() {
+        let profile = split_profile(2);
+        let m = metrics(&profile, Resolution::new(640, 480));
+        let at_rest = crosshair_segments(&profile, &m, 100, 100, 0);
+        let spread = crosshair_segments(&profile, &m, 100, 100, 10);
+        assert_eq!(at_rest.len(), 8);
+        // Left arm: inner part first, outer part directly behind it
+        let (inner, outer) = (at_rest[0].rect, at_rest[4].rect);
+        assert_eq!((at_rest[0].part, at_rest[4].part), ("inner left", "outer left"));
+        assert_eq!([inner[2] - inner[0], outer[2] - outer[0]], [2, 2]);
+        assert_eq!(outer[2], inner[0]);
+        assert_eq!((at_rest[0].alpha, at_rest[4].alpha), (1.0, 0.3));
+        assert_eq!(spread[0].rect[0], inner[0] - 10);
+        assert_eq!(spread[4].rect[0], outer[0] - 3);
+    }
+
+    #[test]
+    fn dynamic_spread_moves_every_arm_and_static_styles_ignore_it() {
+        for (style, shift) in [(3, 6), (4, 0)] {
+            let profile = split_profile(style);
+            let m = metrics(&profile, Resolution::new(640, 480));
+            let at_rest = crosshair_segments(&profile, &m, 100, 100, 0);
+            let spread = crosshair_segments(&profile, &m, 100, 100, 6);
+            assert_eq!(at_rest.len(), 4, "style {}", style);
+            assert_eq!(spread[0].rect[0], at_rest[0].rect[0] - shift, "left, style {}", style);
+            assert_eq!(spread[1].rect[0], at_rest[1].rect[0] + shift, "right, style {}", style);
+            assert_eq!(spread[2].rect[1], at_rest[2].rect[1] - shift, "top, style {}", style);
+            assert_eq!(spread[3].rect[1], at_rest[3].rect[1] + shift, "bottom, style {}", style);
+        }
+    }
+
+    #[test]
+    fn parts_without_length_are_left_out_and_the_rest_keep_their_names() {
+        let mut profile = split_profile(4);
+        (profile.size, profile.dot) = (0.0, true);
+        let m = metrics(&profile, Resolution::new(640, 480));
+        let parts: Vec<&str> = crosshair_segments(&profile, &m, 100, 100, 0).iter().map(|s| s.part).collect();
+        assert_eq!(parts, ["dot"]);
+
+        let mut profile = split_profile(2);
+        profile.dot = true;
+        for (ratio, expected) in [
+            (0.0, ["inner left", "inner right", "inner top", "inner bottom", "dot"]),
+            (1.0, ["outer left", "outer right", "outer top", "outer bottom", "dot"]),
+        ] {
+            profile.dynamic_maxdist_split_ratio = ratio;
+            let m = metrics(&profile, Resolution::new(640, 480));
+            let parts: Vec<&str> = crosshair_segments(&profile, &m, 100, 100, 0).iter().map(|s| s.part).collect();
+            assert_eq!(parts, expected, "ratio {}", ratio);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::apply;
+use crate::kv3;
+
+/// Degrees per mouse count at sensitivity 1 in Source games (`m_yaw` default).
+pub const SOURCE_YAW: f32 = 0.022;
+const CM_PER_INCH: f32 = 2.54;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Game {
+    Cs2,
+    Csgo,
+    Valorant,
+    Apex,
+    Overwatch,
+}
+
+impl Game {
+    pub const ALL: [Game; 5] = [Game::Cs2, Game::Csgo, Game::Valorant, Game::Apex, Game::Overwatch];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Game::Cs2 => "CS2",
+            Game::Csgo => "CS:GO",
+            Game::Valorant => "Valorant",
+            Game::Apex => "Apex Legends",
+            Game::Overwatch => "Overwatch 2",
+        }
+    }
+
+    /// Degrees turned per mouse count at sensitivity 1.
+    pub fn yaw(self) -> f32 {
+        match self {
+            Game::Cs2 | Game::Csgo | Game::Apex => SOURCE_YAW,
+            Game::Valorant => 0.07,
+            Game::Overwatch => 0.0066,
+        }
+    }
+}
+
+/// The mouse settings of an account's user convars.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sensitivity {
+    pub sensitivity: f32,
+    pub zoom_ratio: f32,
+    pub m_yaw: f32,
+    pub m_pitch: f32,
+}
+
+impl Default for Sensitivity {
+    fn default() -> Self {
+        Self { sensitivity: 1.25, zoom_ratio: 1.0, m_yaw: SOURCE_YAW, m_pitch: SOURCE_YAW }
+    }
+}
+
+impl Sensitivity {
+    /// Reads the values from a user convars document, missing ones keep the game's defaults.
+    pub fn from_document(document: &kv3::Document) -> Self {
+        let number = |convar: &str| apply::convar(document, convar).and_then(|v| v.trim().parse::<f32>().ok());
+        let defaults = Self::default();
+        Self {
+            sensitivity: number("sensitivity").unwrap_or(defaults.sensitivity),
+            zoom_ratio: number("zoom_sensitivity_ratio").unwrap_or(defaults.zoom_ratio),
+            m_yaw: number("m_yaw").unwrap_or(defaults.m_yaw),
+            m_pitch: number("m_pitch").unwrap_or(defaults.m_pitch),
+        }
+    }
+
+    /// `m_yaw`, or the default if the file sets it to zero or less, which the game does not turn with.
+    pub fn yaw(&self) -> f32 {
+        valid_yaw(self.m_yaw)
+    }
+
+    /// `(convar, value)` pairs written on apply. `m_yaw` and `m_pitch` are left alone so a
+    /// deliberately changed yaw on the target is not reset.
+    pub fn convars(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("sensitivity", format!("{:.6}", self.sensitivity)),
+            ("zoom_sensitivity_ratio", format!("{:.6}", self.zoom_ratio)),
+        ]
+    }
+}
+
+pub fn edpi(sensitivity: f32, dpi: f32) -> f32 {
+    sensitivity * dpi
+}
+
+/// Mouse travel in centimeters for a full turn.
+pub fn cm_per_360(sensitivity: f32, yaw: f32, dpi: f32) -> f32 {
+    let degrees_per_inch = sensitivity * yaw * dpi;
+    if degrees_per_inch <= 0.0 { return f32::INFINITY; }
+    360.0 / degrees_per_inch * CM_PER_INCH
+}
+
+fn valid_yaw(yaw: f32) -> f32 {
+    if yaw > 0.0 && yaw.is_finite() { yaw } else { SOURCE_YAW }
+}
+
+/// The sensitivity that turns the same angle per count with `to_yaw` as `sensitivity` does
+/// with `from_yaw`. A yaw of zero or less falls back to [`SOURCE_YAW`].
+pub fn convert(sensitivity: f32, from_yaw: f32, to_yaw: f32) -> f32 {
+    sensitivity * valid_yaw(from_yaw) / valid_yaw(to_yaw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_games_at_equal_cm_per_360() {
+        let cs2 = convert(0.3, Game::Valorant.yaw(), Game::Cs2.yaw());
+        assert!((cs2 - 0.954_545).abs() < 1e-4);
+        let (a, b) = (cm_per_360(0.3, Game::Valorant.yaw(), 800.0), cm_per_360(cs2, Game::Cs2.yaw(), 800.0));
+        assert!((a - b).abs() < 1e-3);
+        assert!((cm_per_360(1.0, SOURCE_YAW, 800.0) - 51.95).abs() < 0.01);
+        assert_eq!(edpi(1.5, 800.0), 1200.0);
+    }
+
+    #[test]
+    fn zero_yaw_falls_back_to_the_source_yaw() {
+        assert_eq!(convert(2.0, SOURCE_YAW, 0.0), 2.0);
+        assert_eq!(convert(2.0, SOURCE_YAW, -1.0), 2.0);
+        assert_eq!(Sensitivity { m_yaw: 0.0, ..Sensitivity::default() }.yaw(), SOURCE_YAW);
+    }
+}
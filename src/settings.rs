@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+const SETTINGS_FILE: &str = "cs2man_settings.json";
+
+/// Background image and view of the crosshair preview.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreviewBackground {
+    pub path: String,
+    /// View center in background image pixels.
+    pub pan: [f32; 2],
+    /// Background pixels are magnified by this factor, the crosshair is not.
+    pub zoom: f32,
+}
+
+impl PreviewBackground {
+    pub fn new(path: String) -> Self {
+        Self { path, pan: [0.0, 0.0], zoom: 1.0 }
+    }
+}
+
+/// Small per-user state that survives restarts, stored next to the crosshair library.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppSettings {
+    /// Preview background per Steam account id, `default` when no account is selected.
+    #[serde(default)]
+    pub preview_backgrounds: BTreeMap<String, PreviewBackground>,
+    #[serde(default)]
+    pub recent_backgrounds: Vec<String>,
+}
+
+impl AppSettings {
+    pub fn load() -> Self {
+        fs::read_to_string(PathBuf::from(SETTINGS_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Ok(content) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(PathBuf::from(SETTINGS_FILE), content);
+        }
+    }
+
+    pub fn remember_background(&mut self, path: &str) {
+        const MAX_RECENT: usize = 8;
+        self.recent_backgrounds.retain(|p| p != path);
+        self.recent_backgrounds.insert(0, path.to_string());
+        self.recent_backgrounds.truncate(MAX_RECENT);
+    }
+}
@@ -0,0 +1,192 @@
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use crate::CrosshairProfile;
+
+const DICTIONARY: &str = "ABCDEFGHJKLMNOPQRSTUVWXYZabcdefhijkmnopqrstuvwxyz23456789";
+const DICTIONARY_LENGTH: u64 = 57;
+
+pub fn checksum(bytes: &[u8]) -> u8 {
+    bytes[1..18].iter().fold(0u16, |acc, &b| acc.wrapping_add(b as u16)) as u8
+}
+
+/// Decodes the 18 raw bytes of a `CSGO-xxxxx-xxxxx-xxxxx-xxxxx-xxxxx` share code.
+/// The checksum is not verified here, see [`is_valid`].
+pub fn decode_bytes(code: &str) -> Result<Vec<u8>, String> {
+    if !code.starts_with("CSGO-") || code.matches('-').count() != 5 {
+        return Err(format!("Invalid code format: {}", code));
+    }
+    let parts: Vec<&str> = code.split('-').collect();
+    if parts.len() != 6 || parts[0] != "CSGO" {
+        return Err(format!("Invalid parts: {:?}", parts));
+    }
+    let chars: String = parts[1..].join("");
+    if chars.len() != 25 {
+        return Err(format!("Invalid character length: {}", chars.len()));
+    }
+
+    let mut num = BigUint::zero();
+    let base = BigUint::from(DICTIONARY_LENGTH);
+    for (i, c) in chars.chars().rev().enumerate() {
+        let idx = match DICTIONARY.find(c) {
+            Some(idx) => idx as u64,
+            None => return Err(format!("Invalid character '{}' at position {}", c, i)),
+        };
+        num = num * &base + BigUint::from(idx);
+    }
+
+    // 25 base-57 digits can hold more than 144 bits, such codes are not share codes.
+    let digits = num.to_bytes_be();
+    if digits.len() > 18 {
+        return Err(format!("Code value does not fit in 18 bytes: {}", code));
+    }
+    let mut bytes = vec![0u8; 18 - digits.len()];
+    bytes.extend_from_slice(&digits);
+    Ok(bytes)
+}
+
+/// True if the code is well formed and its checksum byte matches.
+pub fn is_valid(code: &str) -> bool {
+    decode_bytes(code).map(|bytes| bytes.len() == 18 && bytes[0] == checksum(&bytes)).unwrap_or(false)
+}
+
+pub fn profile_from_bytes(bytes: &[u8], name: String, code: &str) -> CrosshairProfile {
+    CrosshairProfile {
+        gap: (bytes[2] as i8) as f32 / 10.0,
+        outline_thickness: bytes[3] as f32 / 2.0,
+        red: bytes[4],
+        green: bytes[5],
+        blue: bytes[6],
+        alpha: bytes[7],
+        dynamic_splitdist: bytes[8] & 0x7f,
+        recoil: (bytes[8] >> 7) != 0,
+        fixed_gap: (bytes[9] as i8) as f32 / 10.0,
+        color: bytes[10] & 0x07,
+        draw_outline: (bytes[10] & 0x08) != 0,
+        dynamic_splitalpha_innermod: ((bytes[10] >> 4) as f32) / 10.0,
+        dynamic_splitalpha_outermod: ((bytes[11] & 0x0f) as f32) / 10.0,
+        dynamic_maxdist_split_ratio: ((bytes[11] >> 4) as f32) / 10.0,
+        thickness: bytes[12] as f32 / 10.0,
+        style: (bytes[13] & 0x0f) >> 1,
+        dot: (bytes[13] & 0x10) != 0,
+        gap_use_weapon_value: (bytes[13] & 0x20) != 0,
+        use_alpha: (bytes[13] & 0x40) != 0,
+        t: (bytes[13] & 0x80) != 0,
+        size: (((bytes[15] & 0x1f) as u16) << 8 | bytes[14] as u16) as f32 / 10.0,
+        name,
+        original_code: Some(code.to_string()),
+        tags: Vec::new(),
+        folder: String::new(),
+        favorite: false,
+        added_at: crate::library::now_timestamp(),
+    }
+}
+
+/// Packs the crosshair settings into the 18 share code bytes, checksum included.
+/// Two profiles with equal bytes render identically in game.
+pub fn settings_bytes(profile: &CrosshairProfile) -> [u8; 18] {
+    let size = (profile.size * 10.0).round().clamp(0.0, 8191.0) as u16;
+    let mut bytes = [
+        0, // Checksum placeholder
+        1, // Version/ID byte
+        ((profile.gap * 10.0).round() as i8) as u8,
+        (profile.outline_thickness * 2.0).round().min(255.0) as u8,
+        profile.red,
+        profile.green,
+        profile.blue,
+        profile.alpha,
+        (profile.dynamic_splitdist & 0x7f) | ((profile.recoil as u8) << 7),
+        ((profile.fixed_gap * 10.0).round() as i8) as u8,
+        (profile.color & 0x07) | ((profile.draw_outline as u8) << 3) | (((profile.dynamic_splitalpha_innermod * 10.0).round().min(15.0) as u8) << 4),
+        ((profile.dynamic_splitalpha_outermod * 10.0).round().min(15.0) as u8 & 0x0F) | (((profile.dynamic_maxdist_split_ratio * 10.0).round().min(15.0) as u8) << 4),
+        (profile.thickness * 10.0).round().min(255.0) as u8,
+        ((profile.style & 0x07) << 1) |
+        ((profile.dot as u8) << 4) |
+        ((profile.gap_use_weapon_value as u8) << 5) |
+        ((profile.use_alpha as u8) << 6) |
+        ((profile.t as u8) << 7),
+        size as u8,
+        (size >> 8) as u8 & 0x1f,
+        0,
+        0,
+    ];
+    bytes[0] = checksum(&bytes);
+    bytes
+}
+
+/// True if both profiles decode to the same crosshair, regardless of name or original code.
+pub fn same_settings(a: &CrosshairProfile, b: &CrosshairProfile) -> bool {
+    settings_bytes(a) == settings_bytes(b)
+}
+
+/// The code to share for a profile: its original code while unedited, otherwise the
+/// encoded current settings.
+pub fn code(profile: &CrosshairProfile) -> String {
+    profile.original_code.clone().unwrap_or_else(|| encode(profile))
+}
+
+/// Encodes the current settings of the profile, ignoring its `original_code`.
+pub fn encode(profile: &CrosshairProfile) -> String {
+    let bytes = settings_bytes(profile);
+
+    let mut num = BigUint::zero();
+    let base = BigUint::from(256u64);
+    for &byte in bytes.iter() {
+        num = num * &base + BigUint::from(byte as u64);
+    }
+
+    // The first character holds the least significant digit, mirroring `decode_bytes`.
+    let mut code = String::with_capacity(25);
+    let dict_base = BigUint::from(DICTIONARY_LENGTH);
+    for _ in 0..25 {
+        let remainder = (&num % &dict_base).to_u64_digits().first().copied().unwrap_or(0) as usize;
+        num /= &dict_base;
+        code.push(DICTIONARY.chars().nth(remainder).unwrap_or('A'));
+    }
+
+    format!("CSGO-{}-{}-{}-{}-{}", &code[0..5], &code[5..10], &code[10..15], &code[15..20], &code[20..25])
+}
+
+/// Device's crosshair, the profile most tests start from.
+#[cfg(test)]
+pub const SAMPLE_CODE: &str = "CSGO-jvnbx-S3xFK-iEJXD-Y27Nd-AO6FP";
+
+/// Decodes a valid share code for tests, named after the code.
+#[cfg(test)]
+pub fn profile_from_code(code: &str) -> CrosshairProfile {
+    profile_from_bytes(&decode_bytes(code).unwrap(), code.to_string(), code)
+}
+
+#[cfg(test)]
+pub fn sample_profile() -> CrosshairProfile {
+    CrosshairProfile { name: "device".to_string(), ..profile_from_code(SAMPLE_CODE) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_codes_larger_than_18_bytes() {
+        // The last character is the most significant digit; 56 * 57^24 needs 146 bits.
+        assert!(decode_bytes("CSGO-AAAAA-AAAAA-AAAAA-AAAAA-AAAA9").is_err());
+        assert!(!is_valid("CSGO-99999-99999-99999-99999-99999"));
+        assert_eq!(decode_bytes("CSGO-AAAAA-AAAAA-AAAAA-AAAAA-AAAAA").unwrap(), vec![0; 18]);
+        assert_eq!(decode_bytes(SAMPLE_CODE).unwrap().len(), 18);
+    }
+
+    #[test]
+    fn encode_round_trips_through_decode() {
+        let mut profile = sample_profile();
+        for (gap, size, style) in [(-2.0, 2.5, 4), (0.5, 10.0, 2), (3.0, 0.0, 5)] {
+            profile.gap = gap;
+            profile.size = size;
+            profile.style = style;
+            let encoded = encode(&profile);
+            assert!(is_valid(&encoded), "{}", encoded);
+            let decoded = profile_from_bytes(&decode_bytes(&encoded).unwrap(), "decoded".to_string(), &encoded);
+            assert!(same_settings(&decoded, &profile), "{}", encoded);
+            assert_eq!((decoded.gap, decoded.size, decoded.style), (gap, size, style));
+        }
+    }
+}
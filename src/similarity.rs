@@ -0,0 +1,125 @@
+use crate::render;
+use crate::CrosshairProfile;
+
+/// Profiles closer than this are reported as near-duplicates on import.
+pub const NEAR_DUPLICATE_DISTANCE: f32 = 0.25;
+
+pub fn srgb_to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+/// CIE L*a*b* (D65 white point) of an sRGB color.
+pub fn srgb_to_lab(rgb: [u8; 3]) -> [f32; 3] {
+    let [r, g, b] = rgb.map(srgb_to_linear);
+    let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+    let f = |t: f32| if t > 0.008856 { t.cbrt() } else { 7.787 * t + 16.0 / 116.0 };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// Perceptual color difference (CIE76 ΔE). About 2.3 is the smallest noticeable step.
+pub fn delta_e(a: [u8; 3], b: [u8; 3]) -> f32 {
+    let (a, b) = (srgb_to_lab(a), srgb_to_lab(b));
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+}
+
+fn flag(a: bool, b: bool) -> f32 {
+    if a == b { 0.0 } else { 1.0 }
+}
+
+/// Additive drawing ignores `cl_crosshairalpha` and uses a fixed alpha instead.
+fn effective_alpha(profile: &CrosshairProfile) -> f32 {
+    if profile.use_alpha { profile.alpha as f32 } else { 200.0 }
+}
+
+/// Distance between two crosshairs on their decoded settings. 0 means visually identical,
+/// each term is scaled so that roughly 1.0 is "clearly different".
+pub fn distance(a: &CrosshairProfile, b: &CrosshairProfile) -> f32 {
+    let color = delta_e(render::crosshair_rgb(a), render::crosshair_rgb(b)) / 50.0;
+    let alpha = (effective_alpha(a) - effective_alpha(b)).abs() / 255.0 + flag(a.use_alpha, b.use_alpha) * 0.25;
+    let size = (a.size - b.size).abs() / 3.0;
+    let gap = (a.gap - b.gap).abs() / 3.0;
+    let thickness = (a.thickness - b.thickness).abs() / 1.0;
+    let style = if a.style == b.style {
+        0.0
+    } else if render::is_dynamic_style(a.style) == render::is_dynamic_style(b.style) {
+        0.5
+    } else {
+        1.0
+    };
+    let outline = if a.draw_outline && b.draw_outline {
+        (a.outline_thickness - b.outline_thickness).abs() / 2.0
+    } else {
+        flag(a.draw_outline, b.draw_outline) * 0.5
+    };
+    let shape = (flag(a.dot, b.dot) + flag(a.t, b.t)) * 0.5;
+    color + alpha * 0.5 + size + gap + thickness + style + outline + shape
+}
+
+/// Library entries ordered by distance to `target`, closest first.
+pub fn rank(library: &[CrosshairProfile], target: &CrosshairProfile, limit: usize) -> Vec<(usize, f32)> {
+    let mut ranked: Vec<(usize, f32)> = library.iter().enumerate().map(|(idx, p)| (idx, distance(p, target))).collect();
+    ranked.sort_by(|a, b| a.1.total_cmp(&b.1));
+    ranked.truncate(limit);
+    ranked
+}
+
+/// Name of the closest entry in `candidates` within [`NEAR_DUPLICATE_DISTANCE`], if any.
+pub fn near_duplicate<'a>(candidates: impl IntoIterator<Item = &'a CrosshairProfile>, target: &CrosshairProfile) -> Option<String> {
+    candidates.into_iter()
+        .map(|p| (p, distance(p, target)))
+        .filter(|(_, d)| *d < NEAR_DUPLICATE_DISTANCE)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(p, _)| p.name.clone())
+}
+
+/// Similarity in percent for display, 100% for identical settings.
+pub fn similarity_percent(distance: f32) -> f32 {
+    100.0 / (1.0 + distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::share_code;
+
+    fn library() -> Vec<CrosshairProfile> {
+        ["CSGO-TpORA-p9Ley-TLQ3P-HzXJY-U9z6A", "CSGO-H3Wb2-YV2FB-VPipW-dx2td-hej5P", share_code::SAMPLE_CODE].map(share_code::profile_from_code).to_vec()
+    }
+
+    #[test]
+    fn identical_profiles_have_zero_distance() {
+        for profile in library() {
+            assert_eq!(distance(&profile, &profile), 0.0);
+            assert_eq!(similarity_percent(distance(&profile, &profile)), 100.0);
+        }
+        assert_eq!(delta_e([12, 200, 90], [12, 200, 90]), 0.0);
+    }
+
+    #[test]
+    fn distance_is_symmetric() {
+        let library = library();
+        for a in &library {
+            for b in &library {
+                assert_eq!(distance(a, b), distance(b, a), "{} / {}", a.name, b.name);
+            }
+        }
+    }
+
+    #[test]
+    fn rank_orders_by_growing_difference() {
+        let target = library()[2].clone();
+        let mut library = vec![target.clone(); 3];
+        library[0].size += 3.0;
+        library[0].red = library[0].red.wrapping_add(120);
+        library[1].size += 1.0;
+        let ranked = rank(&library, &target, 3);
+        assert_eq!(ranked.iter().map(|(idx, _)| *idx).collect::<Vec<_>>(), vec![2, 1, 0]);
+        assert!(ranked.windows(2).all(|w| w[0].1 < w[1].1));
+        assert_eq!(rank(&library, &target, 1).len(), 1);
+        assert_eq!(near_duplicate(&library, &target).as_deref(), Some(target.name.as_str()));
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::kv3;
+
+/// What a CS2 settings file in the account's `cfg` folder holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VcfgKind {
+    UserConvars,
+    KeyBindings,
+    MachineConvars,
+    Video,
+    Other,
+}
+
+impl VcfgKind {
+    /// Classifies by file name, e.g. `cs2_user_convars_0_slot0.vcfg`.
+    pub fn classify(file_name: &str) -> Self {
+        let name = file_name.to_ascii_lowercase();
+        if name.starts_with("cs2_user_convars") {
+            VcfgKind::UserConvars
+        } else if name.starts_with("cs2_user_keys") {
+            VcfgKind::KeyBindings
+        } else if name.starts_with("cs2_machine_convars") {
+            VcfgKind::MachineConvars
+        } else if name.starts_with("cs2_video") {
+            VcfgKind::Video
+        } else {
+            VcfgKind::Other
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            VcfgKind::UserConvars => "User convars",
+            VcfgKind::KeyBindings => "Key bindings",
+            VcfgKind::MachineConvars => "Machine convars",
+            VcfgKind::Video => "Video",
+            VcfgKind::Other => "Other",
+        }
+    }
+
+    /// Top level section holding the file's entries.
+    pub fn section(self) -> Option<&'static str> {
+        match self {
+            VcfgKind::UserConvars | VcfgKind::MachineConvars => Some("convars"),
+            VcfgKind::KeyBindings => Some("bindings"),
+            VcfgKind::Video | VcfgKind::Other => None,
+        }
+    }
+
+    pub fn purpose(self) -> &'static str {
+        match self {
+            VcfgKind::UserConvars => "Game settings that follow the Steam account: crosshair, HUD, radar, audio, sensitivity",
+            VcfgKind::KeyBindings => "Key and mouse button bindings",
+            VcfgKind::MachineConvars => "Settings tied to this computer",
+            VcfgKind::Video => "Resolution, display mode and graphics quality",
+            VcfgKind::Other => "Unrecognized settings file",
+        }
+    }
+}
+
+/// A settings file CS2 manages itself, as opposed to user written `.cfg` scripts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VcfgFile {
+    pub name: String,
+    pub kind: VcfgKind,
+    /// The `slotN` suffix of per-slot files such as `cs2_user_keys_0_slot0.vcfg`.
+    pub slot: Option<u32>,
+    pub size: u64,
+    /// Number of entries in the main section, `None` if the file could not be parsed.
+    #[serde(default)]
+    pub entries: Option<usize>,
+    /// Hash of the file content at scan time, so source and target can be compared
+    /// without reading both files again.
+    #[serde(default)]
+    pub content_hash: u64,
+}
+
+impl VcfgFile {
+    fn read(path: &Path, name: &str, size: u64) -> Self {
+        let stem = name.trim_end_matches(".vcfg").trim_end_matches(".txt");
+        let slot = stem.rsplit_once("_slot").and_then(|(_, n)| n.parse().ok());
+        let kind = VcfgKind::classify(name);
+        let content = fs::read(path).unwrap_or_default();
+        let entries = kind.section().and_then(|section| {
+            let document = kv3::parse(std::str::from_utf8(&content).ok()?).ok()?;
+            Some(document.root_object()?.get(section)?.as_object()?.iter().count())
+        });
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        Self { name: name.to_string(), kind, slot, size, entries, content_hash: hasher.finish() }
+    }
+
+    /// How this file compares to the file of the same name in `target_files`.
+    pub fn status(&self, target_files: &[VcfgFile]) -> &'static str {
+        match target_files.iter().find(|t| t.name == self.name) {
+            None => "missing in target",
+            Some(t) if t.size == self.size && t.content_hash == self.content_hash => "same as target",
+            Some(_) => "differs",
+        }
+    }
+}
+
+/// True for the files handled by [`discover`]: every `.vcfg` plus the `cs2_video.txt` family.
+pub fn is_settings_file(file_name: &str) -> bool {
+    let lower = file_name.to_ascii_lowercase();
+    lower.ends_with(".vcfg") || (lower.starts_with("cs2_video") && lower.ends_with(".txt"))
+}
+
+/// Settings files in a `cfg` folder, sorted by kind and name.
+pub fn discover(cfg_dir: &Path) -> Vec<VcfgFile> {
+    let Ok(entries) = fs::read_dir(cfg_dir) else { return Vec::new() };
+    let mut files: Vec<VcfgFile> = entries.flatten()
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            is_settings_file(&name).then(|| VcfgFile::read(&entry.path(), &name, size))
+        })
+        .collect();
+    files.sort_by(|a, b| (a.kind as u8, &a.name).cmp(&(b.kind as u8, &b.name)));
+    files
+}
+
+/// Copies `path` to `<name>.backup.<timestamp>` next to it and returns the backup path.
+/// Further backups within the same second get a `.1`, `.2`, … suffix.
+pub fn backup_file(path: &Path) -> Result<PathBuf, String> {
+    let file_name = path.file_name().and_then(|n| n.to_str()).ok_or("Invalid file name")?;
+    let base = format!("{}.backup.{}", file_name, crate::library::now_timestamp());
+    let mut backup_path = path.with_file_name(&base);
+    let mut n = 1;
+    while backup_path.exists() {
+        backup_path = path.with_file_name(format!("{}.{}", base, n));
+        n += 1;
+    }
+    fs::copy(path, &backup_path).map_err(|e| format!("Could not back up {}: {}", path.display(), e))?;
+    Ok(backup_path)
+}
+
+/// Copies one settings file between two `cfg` folders, backing up the target first.
+pub fn copy_file(name: &str, from_dir: &Path, to_dir: &Path, backup: bool) -> Result<Option<PathBuf>, String> {
+    let target = to_dir.join(name);
+    let backup_path = if backup && target.exists() { Some(backup_file(&target)?) } else { None };
+    fs::create_dir_all(to_dir).map_err(|e| e.to_string())?;
+    fs::copy(from_dir.join(name), &target).map_err(|e| format!("Could not copy {}: {}", name, e))?;
+    Ok(backup_path)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    Same,
+    Removed,
+    Added,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+/// Line diff from `a` to `b` (longest common subsequence). Leading and trailing whitespace
+/// is ignored so reindented files compare equal.
+pub fn diff_lines(a: &str, b: &str) -> Vec<DiffLine> {
+    let a: Vec<&str> = a.lines().collect();
+    let b: Vec<&str> = b.lines().collect();
+    let same = |i: usize, j: usize| a[i].trim() == b[j].trim();
+    let (n, m) = (a.len(), b.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if same(i, j) { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+    let line = |kind, text: &str| DiffLine { kind, text: text.to_string() };
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::with_capacity(n.max(m));
+    while i < n && j < m {
+        if same(i, j) {
+            diff.push(line(DiffKind::Same, b[j]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(line(DiffKind::Removed, a[i]));
+            i += 1;
+        } else {
+            diff.push(line(DiffKind::Added, b[j]));
+            j += 1;
+        }
+    }
+    diff.extend(a[i..].iter().map(|text| line(DiffKind::Removed, text)));
+    diff.extend(b[j..].iter().map(|text| line(DiffKind::Added, text)));
+    diff
+}
+
+pub fn read_document(path: &Path) -> Result<kv3::Document, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    kv3::parse(&content).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Writes `document` to `path`, backing up the existing file first when `backup` is set.
+pub fn write_document(path: &Path, document: &kv3::Document, backup: bool) -> Result<Option<PathBuf>, String> {
+    let backup_path = if backup && path.exists() { Some(backup_file(path)?) } else { None };
+    fs::write(path, kv3::write(document)).map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+    Ok(backup_path)
+}
+
+/// Diff of `name` between two `cfg` folders, a missing file counts as empty. KV3 files
+/// are compared entry by entry (`convars/cl_crosshairgap = -3`), so only real setting
+/// changes show up; anything else falls back to a plain line diff.
+pub fn diff_files(name: &str, a_dir: &Path, b_dir: &Path) -> Vec<DiffLine> {
+    let read = |dir: &Path| fs::read_to_string(dir.join(name)).unwrap_or_default();
+    let (a, b) = (read(a_dir), read(b_dir));
+    let entries = |text: &str| -> Option<String> {
+        if text.trim().is_empty() { return Some(String::new()); }
+        kv3::parse(text).ok().map(|document| kv3::flatten(&document.root).join("\n"))
+    };
+    match (entries(&a), entries(&b)) {
+        (Some(a), Some(b)) => diff_lines(&a, &b),
+        _ => diff_lines(&a, &b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEYS: &str = "<!-- kv3 -->\n{\n\t\"bindings\" =\n\t{\n\t\t\"W\" = \"+forward\"\n\t\t\"S\" = \"+back\"\n\t}\n}\n";
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cs2man_vcfg_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn classifies_files_and_reads_slots() {
+        assert_eq!(VcfgKind::classify("cs2_user_convars_0_slot0.vcfg"), VcfgKind::UserConvars);
+        assert_eq!(VcfgKind::classify("CS2_USER_KEYS_0_SLOT0.vcfg"), VcfgKind::KeyBindings);
+        assert_eq!(VcfgKind::classify("cs2_machine_convars.vcfg"), VcfgKind::MachineConvars);
+        assert_eq!(VcfgKind::classify("cs2_video.txt"), VcfgKind::Video);
+        assert_eq!(VcfgKind::classify("autoexec.vcfg"), VcfgKind::Other);
+        assert!(is_settings_file("cs2_video.txt.bak.txt") && is_settings_file("a.VCFG") && !is_settings_file("autoexec.cfg"));
+
+        let dir = temp_dir("discover");
+        fs::write(dir.join("cs2_user_keys_0_slot3.vcfg"), KEYS).unwrap();
+        fs::write(dir.join("cs2_machine_convars.vcfg"), "not kv3").unwrap();
+        fs::write(dir.join("autoexec.cfg"), "bind w +forward").unwrap();
+        let files = discover(&dir);
+        let summary: Vec<(&str, Option<u32>, Option<usize>)> = files.iter().map(|f| (f.name.as_str(), f.slot, f.entries)).collect();
+        assert_eq!(summary, [("cs2_user_keys_0_slot3.vcfg", Some(3), Some(2)), ("cs2_machine_convars.vcfg", None, None)]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn status_compares_size_and_content() {
+        let dir = temp_dir("status");
+        let (a, b) = (dir.join("a"), dir.join("b"));
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+        fs::write(a.join("cs2_user_keys_0_slot0.vcfg"), KEYS).unwrap();
+        fs::write(a.join("cs2_video.txt"), "\"setting.fullscreen\" \"1\"").unwrap();
+        fs::write(b.join("cs2_user_keys_0_slot0.vcfg"), KEYS).unwrap();
+        let source = discover(&a);
+        assert_eq!(source[0].status(&discover(&b)), "same as target");
+        assert_eq!(source[1].status(&discover(&b)), "missing in target");
+        fs::write(b.join("cs2_user_keys_0_slot0.vcfg"), KEYS.replace("+back", "+left")).unwrap();
+        assert_eq!(source[0].status(&discover(&b)), "differs");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn diff_lines_ignores_indentation() {
+        let diff = diff_lines("a\n  b\nc", "a\nb\nd\nc");
+        let kinds: Vec<(DiffKind, &str)> = diff.iter().map(|l| (l.kind, l.text.as_str())).collect();
+        assert_eq!(kinds, [(DiffKind::Same, "a"), (DiffKind::Same, "b"), (DiffKind::Added, "d"), (DiffKind::Same, "c")]);
+        let diff = diff_lines("x\ny", "");
+        assert!(diff.iter().all(|l| l.kind == DiffKind::Removed) && diff.len() == 2);
+    }
+
+    #[test]
+    fn backups_in_the_same_second_do_not_overwrite_each_other() {
+        let dir = temp_dir("backup");
+        let path = dir.join("cs2_user_keys_0_slot0.vcfg");
+        fs::write(&path, "first").unwrap();
+        let first = backup_file(&path).unwrap();
+        fs::write(&path, "second").unwrap();
+        let second = backup_file(&path).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(fs::read_to_string(&first).unwrap(), "first");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "second");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,230 @@
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::apply;
+use crate::convar_profiles::LibraryProfile;
+use crate::convars;
+use crate::kv3;
+
+pub const LIBRARY_FILE: &str = "viewmodel_profiles.json";
+
+/// `(label, convar)` of the sliders, their ranges come from the convar database.
+const OFFSETS: [(&str, &str); 4] = [
+    ("FOV", "viewmodel_fov"),
+    ("Offset X", "viewmodel_offset_x"),
+    ("Offset Y", "viewmodel_offset_y"),
+    ("Offset Z", "viewmodel_offset_z"),
+];
+
+/// `viewmodel_presetpos` values; 0 means the offsets were set by hand.
+pub const PRESET_NAMES: [&str; 4] = ["Custom", "Desktop", "Couch", "Classic"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewmodelProfile {
+    pub name: String,
+    pub fov: f32,
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub offset_z: f32,
+    pub preset_pos: u8,
+    pub right_hand: bool,
+    #[serde(default)]
+    pub added_at: u64,
+}
+
+impl Default for ViewmodelProfile {
+    fn default() -> Self {
+        Self::preset(1).expect("preset 1 exists")
+    }
+}
+
+impl LibraryProfile for ViewmodelProfile {
+    fn name_mut(&mut self) -> &mut String {
+        &mut self.name
+    }
+
+    fn set_added_at(&mut self, timestamp: u64) {
+        self.added_at = timestamp;
+    }
+}
+
+impl ViewmodelProfile {
+    /// The offsets the game sets for `viewmodel_presetpos` 1 to 3.
+    pub fn preset(preset_pos: u8) -> Option<Self> {
+        let (fov, offset_x, offset_y, offset_z) = match preset_pos {
+            1 => (60.0, 1.0, 1.0, -1.0),
+            2 => (54.0, 0.0, 0.0, 0.0),
+            3 => (54.0, 2.5, 0.0, -1.5),
+            _ => return None,
+        };
+        Some(Self {
+            name: PRESET_NAMES[preset_pos as usize].to_string(),
+            fov,
+            offset_x,
+            offset_y,
+            offset_z,
+            preset_pos,
+            right_hand: true,
+            added_at: 0,
+        })
+    }
+
+    /// `(convar, value)` pairs in the format of the user convars vcfg.
+    pub fn convars(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("viewmodel_fov", format!("{:.6}", self.fov)),
+            ("viewmodel_offset_x", format!("{:.6}", self.offset_x)),
+            ("viewmodel_offset_y", format!("{:.6}", self.offset_y)),
+            ("viewmodel_offset_z", format!("{:.6}", self.offset_z)),
+            ("viewmodel_presetpos", self.preset_pos.to_string()),
+            ("cl_righthand", self.right_hand.to_string()),
+        ]
+    }
+
+    /// Reads the viewmodel convars of a user convars document. Missing values keep the
+    /// game's defaults.
+    pub fn from_document(document: &kv3::Document, name: String) -> Self {
+        let mut profile = Self { name, ..Self::default() };
+        let number = |convar: &str| apply::convar(document, convar).and_then(|v| v.trim().parse::<f32>().ok());
+        if let Some(fov) = number("viewmodel_fov") { profile.fov = fov; }
+        if let Some(x) = number("viewmodel_offset_x") { profile.offset_x = x; }
+        if let Some(y) = number("viewmodel_offset_y") { profile.offset_y = y; }
+        if let Some(z) = number("viewmodel_offset_z") { profile.offset_z = z; }
+        if let Some(preset) = number("viewmodel_presetpos") { profile.preset_pos = preset.round().clamp(0.0, 3.0) as u8; }
+        if let Some(hand) = apply::convar(document, "cl_righthand") {
+            profile.right_hand = !matches!(hand.trim(), "false" | "0");
+        }
+        profile
+    }
+
+    fn offsets_mut(&mut self) -> [&mut f32; 4] {
+        [&mut self.fov, &mut self.offset_x, &mut self.offset_y, &mut self.offset_z]
+    }
+
+    /// Values the game would clamp.
+    pub fn warnings(&self) -> Vec<String> {
+        let values = [self.fov, self.offset_x, self.offset_y, self.offset_z];
+        OFFSETS.iter().zip(values)
+            .map(|(&(label, convar), value)| (label, value, range(convar)))
+            .filter(|(_, value, range)| !range.contains(value))
+            .map(|(label, value, range)| format!("{} {} is outside the game's range {}..{} and will be clamped", label, value, range.start(), range.end()))
+            .collect()
+    }
+}
+
+pub fn load_library() -> Vec<ViewmodelProfile> {
+    fs::read_to_string(PathBuf::from(LIBRARY_FILE)).ok().and_then(|content| serde_json::from_str(&content).ok()).unwrap_or_default()
+}
+
+pub fn save_library(library: &[ViewmodelProfile]) {
+    if let Ok(content) = serde_json::to_string_pretty(library) {
+        let _ = fs::write(PathBuf::from(LIBRARY_FILE), content);
+    }
+}
+
+/// Range of a viewmodel convar from the convar database.
+pub fn range(convar: &str) -> RangeInclusive<f32> {
+    convars::lookup(convar).map(|info| info.range()).unwrap_or_else(|| panic!("{} is missing from the convar database", convar))
+}
+
+fn offset_input(ui: &mut egui::Ui, label: &str, value: &mut f32, range: RangeInclusive<f32>) -> bool {
+    ui.label(label);
+    let changed = ui.add(egui::Slider::new(value, range).step_by(0.1).fixed_decimals(1)).changed();
+    ui.end_row();
+    changed
+}
+
+/// Editor for all viewmodel values. Picking a preset loads its offsets, editing an offset
+/// switches back to custom. Returns true if anything changed.
+pub fn editor_ui(ui: &mut egui::Ui, profile: &mut ViewmodelProfile) -> bool {
+    let mut changed = false;
+    egui::Grid::new("viewmodel_editor").num_columns(2).show(ui, |ui| {
+        ui.label("Preset");
+        let mut preset = profile.preset_pos;
+        egui::ComboBox::from_id_source("viewmodel_preset")
+            .selected_text(PRESET_NAMES.get(preset as usize).copied().unwrap_or("Unknown"))
+            .show_ui(ui, |ui| {
+                for (idx, name) in PRESET_NAMES.iter().enumerate() {
+                    ui.selectable_value(&mut preset, idx as u8, *name);
+                }
+            });
+        ui.end_row();
+        if preset != profile.preset_pos {
+            if let Some(values) = ViewmodelProfile::preset(preset) {
+                *profile = ViewmodelProfile { name: profile.name.clone(), right_hand: profile.right_hand, added_at: profile.added_at, ..values };
+            }
+            profile.preset_pos = preset;
+            changed = true;
+        }
+
+        let mut offsets = false;
+        for ((label, convar), value) in OFFSETS.into_iter().zip(profile.offsets_mut()) {
+            offsets |= offset_input(ui, label, value, range(convar));
+        }
+        if offsets {
+            profile.preset_pos = 0;
+        }
+        ui.label("Hand");
+        ui.horizontal(|ui| {
+            offsets |= ui.radio_value(&mut profile.right_hand, false, "Left").changed();
+            offsets |= ui.radio_value(&mut profile.right_hand, true, "Right").changed();
+        });
+        ui.end_row();
+        changed |= offsets;
+    });
+    for warning in profile.warnings() {
+        ui.colored_label(egui::Color32::from_rgb(230, 170, 60), format!("⚠ {}", warning));
+    }
+    changed
+}
+
+/// Schematic 16:9 screen with the weapon drawn where the offsets put it: X moves it
+/// sideways, Z up and down, Y and a higher FOV push it away from the camera.
+pub fn schematic_ui(ui: &mut egui::Ui, profile: &ViewmodelProfile) {
+    let width = ui.available_width().clamp(160.0, 320.0);
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(width, width * 9.0 / 16.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    painter.rect(rect, 2.0, visuals.extreme_bg_color, visuals.widgets.noninteractive.bg_stroke);
+    let center = rect.center();
+    let stroke = egui::Stroke::new(1.0, visuals.weak_text_color());
+    painter.line_segment([center - egui::vec2(4.0, 0.0), center + egui::vec2(4.0, 0.0)], stroke);
+    painter.line_segment([center - egui::vec2(0.0, 4.0), center + egui::vec2(0.0, 4.0)], stroke);
+
+    let unit = rect.width() / 24.0;
+    let scale = 60.0 / profile.fov.max(1.0) * (1.0 - profile.offset_y * 0.08);
+    let side = if profile.right_hand { 1.0 } else { -1.0 };
+    let anchor = egui::pos2(
+        center.x + side * (rect.width() * 0.22 + profile.offset_x * unit),
+        rect.bottom() - rect.height() * 0.22 - profile.offset_z * unit,
+    );
+    let body = egui::Rect::from_center_size(anchor, egui::vec2(5.0, 2.2) * unit * scale);
+    let barrel_end = egui::pos2(anchor.x - side * 4.5 * unit * scale, anchor.y - 1.4 * unit * scale);
+    let grip = egui::Rect::from_min_size(
+        egui::pos2(anchor.x + side * 0.6 * unit * scale - 0.6 * unit * scale, body.bottom()),
+        egui::vec2(1.2, 2.0) * unit * scale,
+    );
+    let fill = egui::Color32::from_rgb(120, 130, 145);
+    painter.line_segment([anchor, barrel_end], egui::Stroke::new(0.8 * unit * scale, fill));
+    painter.rect_filled(body, 0.3 * unit * scale, fill);
+    painter.rect_filled(grip, 0.2 * unit * scale, fill);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convars_round_trip_through_a_document() {
+        let profile = ViewmodelProfile { name: "test".to_string(), fov: 68.0, offset_x: 2.5, offset_y: -2.0, offset_z: -2.0, preset_pos: 0, right_hand: false, added_at: 0 };
+        let mut document = kv3::Document::new(kv3::Object::new());
+        apply::set_convars(&mut document, &profile.convars()).unwrap();
+        let text = kv3::write(&document);
+        let read = ViewmodelProfile::from_document(&kv3::parse(&text).unwrap(), "test".to_string());
+        assert_eq!(read, profile);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::raster::RgbaBuffer;
+use crate::similarity::srgb_to_linear;
+
+/// Color vision deficiencies the preview can simulate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorVision {
+    Normal,
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl ColorVision {
+    pub const ALL: [ColorVision; 4] = [ColorVision::Normal, ColorVision::Protanopia, ColorVision::Deuteranopia, ColorVision::Tritanopia];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorVision::Normal => "Normal vision",
+            ColorVision::Protanopia => "Protanopia (no red)",
+            ColorVision::Deuteranopia => "Deuteranopia (no green)",
+            ColorVision::Tritanopia => "Tritanopia (no blue)",
+        }
+    }
+
+    /// Full severity matrices from Machado, Oliveira & Fernandes (2009), applied to linear RGB.
+    fn matrix(self) -> Option<[[f32; 3]; 3]> {
+        match self {
+            ColorVision::Normal => None,
+            ColorVision::Protanopia => Some([
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ]),
+            ColorVision::Deuteranopia => Some([
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ]),
+            ColorVision::Tritanopia => Some([
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ]),
+        }
+    }
+}
+
+fn linear_to_srgb(c: f32) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let c = if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+    (c * 255.0).round() as u8
+}
+
+/// How `rgb` appears with the given color vision.
+pub fn simulate(rgb: [u8; 3], vision: ColorVision) -> [u8; 3] {
+    let Some(m) = vision.matrix() else { return rgb };
+    let lin = rgb.map(srgb_to_linear);
+    let row = |r: [f32; 3]| linear_to_srgb(r[0] * lin[0] + r[1] * lin[1] + r[2] * lin[2]);
+    [row(m[0]), row(m[1]), row(m[2])]
+}
+
+/// Simulates `vision` on every pixel of `buffer`, alpha is kept.
+pub fn simulate_buffer(buffer: &mut RgbaBuffer, vision: ColorVision) {
+    if vision == ColorVision::Normal { return; }
+    for pixel in buffer.pixels.chunks_exact_mut(4) {
+        let [r, g, b] = simulate([pixel[0], pixel[1], pixel[2]], vision);
+        pixel[..3].copy_from_slice(&[r, g, b]);
+    }
+}
+
+/// WCAG relative luminance.
+pub fn luminance(rgb: [u8; 3]) -> f32 {
+    let [r, g, b] = rgb.map(srgb_to_linear);
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// WCAG contrast ratio, from 1 (none) to 21 (black on white).
+pub fn contrast_ratio(a: [u8; 3], b: [u8; 3]) -> f32 {
+    let (la, lb) = (luminance(a), luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// Below this ratio a crosshair is hard to pick out from the pixel behind it.
+pub const LOW_CONTRAST: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContrastReport {
+    /// Contrast against the average background color.
+    pub average: f32,
+    /// Worst contrast against any single background pixel.
+    pub worst: f32,
+    /// Share of background pixels with less than [`LOW_CONTRAST`].
+    pub low_share: f32,
+}
+
+/// How well a crosshair stands out, judged by [`ContrastReport::average`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContrastRating {
+    Good,
+    Fair,
+    Poor,
+}
+
+impl ContrastRating {
+    pub fn label(self) -> &'static str {
+        match self {
+            ContrastRating::Good => "good",
+            ContrastRating::Fair => "fair",
+            ContrastRating::Poor => "poor",
+        }
+    }
+}
+
+impl ContrastReport {
+    pub fn rating(&self) -> ContrastRating {
+        match self.average {
+            r if r >= 4.5 => ContrastRating::Good,
+            r if r >= LOW_CONTRAST => ContrastRating::Fair,
+            _ => ContrastRating::Poor,
+        }
+    }
+}
+
+/// Contrast of `rgb` against the background pixels in `rect` (`[x0, y0, x1, y1]`, clamped
+/// to the buffer), both seen with `vision`.
+pub fn contrast_report(rgb: [u8; 3], background: &RgbaBuffer, rect: [u32; 4], vision: ColorVision) -> Option<ContrastReport> {
+    let rgb = simulate(rgb, vision);
+    let (x1, y1) = (rect[2].min(background.width), rect[3].min(background.height));
+    let mut sum = [0.0f64; 3];
+    let (mut count, mut low, mut worst) = (0usize, 0usize, f32::MAX);
+    for y in rect[1]..y1 {
+        for x in rect[0]..x1 {
+            let i = (y as usize * background.width as usize + x as usize) * 4;
+            let pixel = simulate([background.pixels[i], background.pixels[i + 1], background.pixels[i + 2]], vision);
+            let ratio = contrast_ratio(rgb, pixel);
+            worst = worst.min(ratio);
+            if ratio < LOW_CONTRAST { low += 1; }
+            for c in 0..3 { sum[c] += pixel[c] as f64; }
+            count += 1;
+        }
+    }
+    if count == 0 { return None; }
+    let average = sum.map(|s| (s / count as f64).round() as u8);
+    Some(ContrastReport { average: contrast_ratio(rgb, average), worst, low_share: low as f32 / count as f32 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: [u8; 3], expected: [u8; 3]) {
+        assert!(actual.iter().zip(expected).all(|(a, e)| a.abs_diff(e) <= 1), "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn machado_matrices_match_known_values() {
+        assert_close(simulate([255, 0, 0], ColorVision::Protanopia), [109, 95, 0]);
+        assert_close(simulate([0, 255, 0], ColorVision::Protanopia), [255, 229, 0]);
+        assert_close(simulate([255, 0, 0], ColorVision::Deuteranopia), [163, 144, 0]);
+        assert_close(simulate([0, 255, 0], ColorVision::Deuteranopia), [239, 214, 58]);
+        assert_close(simulate([0, 255, 0], ColorVision::Tritanopia), [0, 247, 217]);
+        for vision in ColorVision::ALL {
+            assert_close(simulate([255, 255, 255], vision), [255, 255, 255]);
+            assert_eq!(simulate([0, 0, 0], vision), [0, 0, 0]);
+        }
+    }
+
+    #[test]
+    fn wcag_contrast_matches_known_values() {
+        assert!((contrast_ratio([0, 0, 0], [255, 255, 255]) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio([255, 255, 255], [0x77, 0x77, 0x77]) - 4.48).abs() < 0.01);
+        assert_eq!(contrast_ratio([10, 200, 30], [10, 200, 30]), 1.0);
+        let report = |average| ContrastReport { average, worst: average, low_share: 0.0 };
+        assert_eq!(report(4.5).rating(), ContrastRating::Good);
+        assert_eq!(report(3.0).rating(), ContrastRating::Fair);
+        assert_eq!(report(2.9).rating().label(), "poor");
+    }
+}